@@ -1,13 +1,91 @@
-use curl::easy::{Easy, List};
+use curl::{
+    easy::{Easy, Easy2, Handler, HttpVersion, List, WriteError},
+    multi::Multi,
+};
+use std::{cell::RefCell, time::Duration};
 
-use crate::cargo::CargoDependency;
+use crate::{cargo::CargoDependency, logger};
 
+#[derive(Clone)]
 pub struct CratesIoResponse {
     pub repository: Option<String>,
     pub description: Option<String>,
     pub latest_version: String,
     pub latest_version_date: Option<String>,
     pub current_version_date: Option<String>,
+    pub versions: Vec<RegistryVersion>,
+}
+
+/// A version of a crate published on crates.io
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RegistryVersion {
+    pub num: String,
+    pub updated_at: Option<String>,
+    pub yanked: bool,
+    pub rust_version: Option<String>,
+    pub features: Vec<String>,
+}
+
+/// A RustSec security advisory affecting the current version of a dependency
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub cvss: Option<String>,
+    pub fixed_versions: Vec<String>,
+}
+
+impl Advisory {
+    // Only the ranges of the crate itself are relevant, an advisory can affect multiple crates
+    fn from_value(value: &serde_json::Value, crate_name: &str) -> Option<Self> {
+        let strings = |value: Option<&serde_json::Value>| -> Vec<String> {
+            value
+                .and_then(|value| value.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Some(Self {
+            id: value.get("id")?.as_str()?.to_string(),
+            aliases: strings(value.get("aliases")),
+            summary: get_string_from_value(value.as_object(), "summary"),
+            cvss: value
+                .get("severity")
+                .and_then(|severity| severity.as_array())
+                .and_then(|severity| {
+                    severity.iter().find(|severity| {
+                        severity.get("type").and_then(|kind| kind.as_str()) == Some("CVSS_V3")
+                    })
+                })
+                .and_then(|severity| severity.get("score")?.as_str())
+                .map(str::to_string),
+            fixed_versions: value
+                .get("affected")
+                .and_then(|affected| affected.as_array())
+                .into_iter()
+                .flatten()
+                .filter(|affected| {
+                    affected
+                        .pointer("/package/ecosystem")
+                        .and_then(|e| e.as_str())
+                        == Some("crates.io")
+                        && affected.pointer("/package/name").and_then(|n| n.as_str())
+                            == Some(crate_name)
+                })
+                .filter_map(|affected| affected.get("ranges")?.as_array())
+                .flatten()
+                .filter_map(|range| range.get("events")?.as_array())
+                .flatten()
+                .filter_map(|event| Some(event.get("fixed")?.as_str()?.to_string()))
+                .collect(),
+        })
+    }
 }
 
 fn get_string_from_value(
@@ -41,6 +119,35 @@ fn get_field_from_versions(
     )
 }
 
+fn get_registry_versions(versions: Option<&Vec<serde_json::Value>>) -> Vec<RegistryVersion> {
+    let Some(versions) = versions else {
+        return vec![];
+    };
+
+    versions
+        .iter()
+        .flat_map(|v| {
+            Some(RegistryVersion {
+                num: v.get("num")?.as_str()?.trim().to_string(),
+                updated_at: v
+                    .get("updated_at")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.trim().to_string()),
+                yanked: v.get("yanked").and_then(|v| v.as_bool()).unwrap_or(false),
+                rust_version: v
+                    .get("rust_version")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.trim().to_string()),
+                features: v
+                    .get("features")
+                    .and_then(|v| v.as_object())
+                    .map(|features| features.keys().cloned().collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
 impl CratesIoResponse {
     fn from_value(value: serde_json::Value, version: &str) -> Self {
         let data = value.get("crate").and_then(|c| c.as_object());
@@ -54,14 +161,100 @@ impl CratesIoResponse {
             description: get_string_from_value(data, "description"),
             latest_version_date: get_field_from_versions(versions, &latest_version, "updated_at"),
             current_version_date: get_field_from_versions(versions, version, "updated_at"),
+            versions: get_registry_versions(versions),
+            latest_version,
+        }
+    }
+
+    // A crate which wasn't looked up, its declared version being the latest known one
+    pub fn empty(version: &str) -> Self {
+        Self {
+            repository: None,
+            description: None,
+            latest_version: version.to_string(),
+            latest_version_date: None,
+            current_version_date: None,
+            versions: vec![],
+        }
+    }
+
+    // The index only lists the versions, the crate details being left for a later lookup
+    fn from_index(body: &str, version: &str) -> Self {
+        let mut versions = body
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter_map(|entry| {
+                Some(RegistryVersion {
+                    num: entry.get("vers")?.as_str()?.to_string(),
+                    updated_at: entry
+                        .get("pubtime")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    yanked: entry
+                        .get("yanked")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    rust_version: entry
+                        .get("rust_version")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    features: ["features", "features2"]
+                        .iter()
+                        .filter_map(|key| entry.get(key)?.as_object())
+                        .flat_map(|features| features.keys().cloned())
+                        .collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        // Like crates.io, the newest versions first
+        versions.reverse();
+
+        let latest_version = versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| semver::Version::parse(&v.num).ok())
+            .filter(|v| v.pre.is_empty())
+            .max()
+            .map_or_else(|| version.to_string(), |v| v.to_string());
+        let date = |num: &str| {
+            versions
+                .iter()
+                .find(|v| v.num == num)
+                .and_then(|v| v.updated_at.clone())
+        };
+
+        Self {
+            repository: None,
+            description: None,
+            latest_version_date: date(&latest_version),
+            current_version_date: date(version),
             latest_version,
+            versions,
         }
     }
+
+    // Prereleases count, so the details are there whichever versions end up being offered
+    pub fn has_newer_version(&self, version: &str) -> bool {
+        let Ok(current) = semver::Version::parse(version) else {
+            return false;
+        };
+
+        self.versions.iter().any(|v| {
+            if v.num == version {
+                return v.yanked;
+            }
+            !v.yanked && semver::Version::parse(&v.num).is_ok_and(|v| v > current)
+        })
+    }
 }
 
-pub fn get_latest_version(
-    CargoDependency { name, version, .. }: &CargoDependency,
-) -> Result<CratesIoResponse, Box<dyn std::error::Error>> {
+thread_local! {
+    // Each worker thread keeps its handle, so the following requests to the same host reuse the
+    // open connection instead of paying a new TLS handshake
+    static HANDLE: RefCell<Easy> = RefCell::new(Easy::new());
+}
+
+fn get_headers(extra_headers: &[String]) -> Result<List, curl::Error> {
     let mut headers = List::new();
 
     let package_name = env!("CARGO_PKG_NAME");
@@ -72,31 +265,329 @@ pub fn get_latest_version(
         "User-Agent: {package_name} ({package_repository})"
     ))?;
 
+    for header in extra_headers {
+        headers.append(header)?;
+    }
+
+    Ok(headers)
+}
+
+fn parse_body(body: &[u8]) -> Result<serde_json::Value, serde_json::Error> {
+    if body.is_empty() {
+        "{}".parse()
+    } else {
+        serde_json::from_slice(body)
+    }
+}
+
+fn get_json(
+    url: &str,
+    extra_headers: &[String],
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    request_json(url, extra_headers, None)
+}
+
+fn request_json(
+    url: &str,
+    extra_headers: &[String],
+    post_body: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let (_, body) = request(url, extra_headers, post_body)?;
+    Ok(parse_body(&body)?)
+}
+
+// The status code and body of the response
+fn request(
+    url: &str,
+    extra_headers: &[String],
+    post_body: Option<&serde_json::Value>,
+) -> Result<(u32, Vec<u8>), Box<dyn std::error::Error>> {
+    let headers = get_headers(extra_headers)?;
+
+    let method = if post_body.is_some() { "POST" } else { "GET" };
+    logger::info(format!("{method} {url}"));
+
+    HANDLE.with_borrow_mut(|handle| {
+        // Resetting the options keeps the connection cache
+        handle.reset();
+        handle.tcp_keepalive(true)?;
+        perform(handle, url, headers, post_body)
+    })
+}
+
+fn perform(
+    handle: &mut Easy,
+    url: &str,
+    mut headers: List,
+    post_body: Option<&serde_json::Value>,
+) -> Result<(u32, Vec<u8>), Box<dyn std::error::Error>> {
+    let method = if post_body.is_some() { "POST" } else { "GET" };
     let mut body = vec![];
-    let mut handle = Easy::new();
 
-    handle.get(true)?;
-    handle.url(&format!("https://crates.io/api/v1/crates/{name}"))?;
+    match post_body {
+        Some(post_body) => {
+            headers.append("Content-Type: application/json")?;
+            handle.post(true)?;
+            handle.post_fields_copy(post_body.to_string().as_bytes())?;
+        }
+        None => handle.get(true)?,
+    }
+    handle.url(url)?;
     handle.http_headers(headers)?;
 
     {
         let mut transfer = handle.transfer();
-        transfer
-            .write_function(|data| {
-                body.extend_from_slice(data);
-                Ok(data.len())
-            })
-            .unwrap();
-        transfer.perform().unwrap();
+        transfer.write_function(|data| {
+            body.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
     }
 
-    let response = if body.is_empty() {
-        "{}".parse()?
-    } else {
-        serde_json::from_slice(&body)?
+    let code = handle.response_code()?;
+    logger::debug(format!(
+        "{method} {url} returned {code} with {} bytes",
+        body.len()
+    ));
+
+    Ok((code, body))
+}
+
+pub fn get_latest_version(
+    dependency: &CargoDependency,
+) -> Result<CratesIoResponse, Box<dyn std::error::Error>> {
+    let url = format!(
+        "https://crates.io/api/v1/crates/{}",
+        dependency.crate_name()
+    );
+    let (code, body) = request(&url, &[], None)?;
+    check_status(&url, code)?;
+
+    Ok(CratesIoResponse::from_value(
+        parse_body(&body)?,
+        &dependency.version,
+    ))
+}
+
+// The JSON errors of a rate limited or failing API would otherwise read as a crate without any
+// version, passing for up to date
+fn check_status(url: &str, code: u32) -> Result<(), String> {
+    match code {
+        200..=299 => Ok(()),
+        code => Err(format!("{url} returned {code}")),
+    }
+}
+
+// The sparse index serves the versions from a CDN, much cheaper than the crates.io API. `None`
+// when the crate isn't published there, e.g. a private crate.
+pub fn get_index_versions(
+    dependency: &CargoDependency,
+) -> Result<Option<CratesIoResponse>, Box<dyn std::error::Error>> {
+    let url = get_index_url(dependency.crate_name());
+    let (code, body) = request(&url, &[], None)?;
+
+    Ok(parse_index_response(code, &body, &dependency.version).map_err(|e| format!("{url} {e}"))?)
+}
+
+// Like cargo, these codes mean the crate doesn't exist, the other errors such as rate limiting
+// would otherwise pass for a crate without any newer version
+fn parse_index_response(
+    code: u32,
+    body: &[u8],
+    version: &str,
+) -> Result<Option<CratesIoResponse>, String> {
+    match code {
+        404 | 410 | 451 => Ok(None),
+        200..=299 => Ok(Some(CratesIoResponse::from_index(
+            &String::from_utf8_lossy(body),
+            version,
+        ))),
+        code => Err(format!("returned {code}")),
+    }
+}
+
+fn get_index_url(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    let prefix = match name.len() {
+        1 => "1".to_string(),
+        2 => "2".to_string(),
+        3 => format!("3/{}", &name[..1]),
+        _ => format!("{}/{}", &name[..2], &name[2..4]),
     };
 
-    Ok(CratesIoResponse::from_value(response, version))
+    format!("https://index.crates.io/{prefix}/{name}")
+}
+
+// Connections opened to crates.io when multiplexing, each carrying many concurrent streams
+const MAX_MULTIPLEXED_CONNECTIONS: usize = 2;
+
+pub fn supports_multiplexing() -> bool {
+    curl::Version::get().feature_http2()
+}
+
+// The body of a transfer, or why it failed
+type TransferBody = Result<Vec<u8>, String>;
+
+struct Collector(Vec<u8>);
+
+impl Handler for Collector {
+    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
+        self.0.extend_from_slice(data);
+        Ok(data.len())
+    }
+}
+
+// Crates.io serves HTTP/2, so the lookups are multiplexed over a few connections instead of
+// waiting for each request in turn, returning the responses in the order of the dependencies
+pub fn get_latest_versions(
+    dependencies: &[&CargoDependency],
+) -> Vec<Result<CratesIoResponse, String>> {
+    match get_multiplexed_bodies(dependencies) {
+        Ok(bodies) => bodies
+            .into_iter()
+            .zip(dependencies)
+            .map(|(body, dependency)| {
+                let response = parse_body(&body?).map_err(|e| e.to_string())?;
+                Ok(CratesIoResponse::from_value(response, &dependency.version))
+            })
+            .collect(),
+        Err(e) => dependencies.iter().map(|_| Err(e.to_string())).collect(),
+    }
+}
+
+fn get_multiplexed_bodies(
+    dependencies: &[&CargoDependency],
+) -> Result<Vec<TransferBody>, Box<dyn std::error::Error>> {
+    let mut multi = Multi::new();
+    multi.pipelining(false, true)?;
+    multi.set_max_host_connections(MAX_MULTIPLEXED_CONNECTIONS)?;
+
+    let mut handles = vec![];
+    for (token, dependency) in dependencies.iter().enumerate() {
+        let url = format!(
+            "https://crates.io/api/v1/crates/{}",
+            dependency.crate_name()
+        );
+        logger::info(format!("GET {url}"));
+
+        let mut easy = Easy2::new(Collector(vec![]));
+        easy.get(true)?;
+        easy.url(&url)?;
+        easy.http_headers(get_headers(&[])?)?;
+        easy.http_version(HttpVersion::V2TLS)?;
+        // Waits for the connection to be established so the requests share it
+        easy.pipewait(true)?;
+
+        let mut handle = multi.add2(easy)?;
+        handle.set_token(token)?;
+        handles.push(handle);
+    }
+
+    while multi.perform()? > 0 {
+        multi.wait(&mut [], Duration::from_secs(1))?;
+    }
+
+    let mut errors = vec![None; handles.len()];
+    multi.messages(|message| {
+        if let (Ok(token), Some(Err(e))) = (message.token(), message.result()) {
+            errors[token] = Some(e.to_string());
+        }
+    });
+
+    handles
+        .into_iter()
+        .zip(errors)
+        .map(|(handle, error)| {
+            let mut easy = multi.remove2(handle)?;
+            let url = easy.effective_url()?.unwrap_or_default().to_string();
+            let code = easy.response_code()?;
+            logger::debug(format!(
+                "GET {url} returned {code} with {} bytes",
+                easy.get_ref().0.len()
+            ));
+
+            Ok(match error {
+                Some(error) => Err(error),
+                None => check_status(&url, code).map(|_| std::mem::take(&mut easy.get_mut().0)),
+            })
+        })
+        .collect()
+}
+
+// The advisories of the RustSec database affecting the version, as served by OSV
+pub fn get_advisories(
+    crate_name: &str,
+    version: &str,
+) -> Result<Vec<Advisory>, Box<dyn std::error::Error>> {
+    let response = request_json(
+        "https://api.osv.dev/v1/query",
+        &[],
+        Some(&serde_json::json!({
+            "package": { "name": crate_name, "ecosystem": "crates.io" },
+            "version": version,
+        })),
+    )?;
+
+    Ok(response
+        .get("vulns")
+        .and_then(|vulns| vulns.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|vuln| Advisory::from_value(vuln, crate_name))
+        .collect())
+}
+
+fn get_github_headers() -> Vec<String> {
+    let mut headers = vec!["Accept: application/vnd.github+json".to_string()];
+
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        headers.push(format!("Authorization: Bearer {token}"));
+    }
+
+    headers
+}
+
+// Unauthenticated clients only get 60 requests an hour from the GitHub API
+pub fn has_github_token() -> bool {
+    std::env::var("GITHUB_TOKEN").is_ok_and(|token| !token.is_empty())
+}
+
+// `None` when there is no release for the tag, a rate limited request being an error
+pub fn get_github_release_body(
+    owner: &str,
+    repo: &str,
+    tag: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/tags/{tag}");
+    let (code, body) = request(&url, &get_github_headers(), None)?;
+    if code == 404 {
+        return Ok(None);
+    }
+    check_status(&url, code)?;
+
+    Ok(parse_body(&body)?
+        .get("body")
+        .and_then(|body| body.as_str())
+        .map(|body| body.to_string()))
+}
+
+// The version and publication date of the latest stable Rust release, tagged as `1.82.0`
+pub fn get_latest_rust_release() -> Result<(String, Option<String>), Box<dyn std::error::Error>> {
+    let response = get_json(
+        "https://api.github.com/repos/rust-lang/rust/releases/latest",
+        &get_github_headers(),
+    )?;
+
+    let version = response
+        .get("tag_name")
+        .and_then(|tag| tag.as_str())
+        .ok_or("missing tag of the latest Rust release")?;
+    let date = response
+        .get("published_at")
+        .and_then(|date| date.as_str())
+        .map(str::to_string);
+
+    Ok((version.to_string(), date))
 }
 
 #[cfg(test)]
@@ -120,6 +611,16 @@ mod tests {
                     "num": "0.2.0",
                     "updated_at": "2023-07-02T00:00:00Z"
                 },
+                {
+                    "num": "0.3.0-alpha.1",
+                    "updated_at": "2023-07-03T00:00:00Z",
+                    "yanked": true,
+                    "rust_version": "1.70",
+                    "features": {
+                        "std": [],
+                        "default": ["std"]
+                    }
+                },
                 {}
             ]
         });
@@ -140,6 +641,114 @@ mod tests {
             response.current_version_date,
             Some("2023-07-01T00:00:00Z".to_string())
         );
+        assert_eq!(response.versions.len(), 3);
+        assert_eq!(
+            response.versions[2],
+            RegistryVersion {
+                num: "0.3.0-alpha.1".to_string(),
+                updated_at: Some("2023-07-03T00:00:00Z".to_string()),
+                yanked: true,
+                rust_version: Some("1.70".to_string()),
+                features: vec!["default".to_string(), "std".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_advisory_from_value() {
+        let value = serde_json::json!({
+            "id": "RUSTSEC-2024-0001",
+            "aliases": ["GHSA-xxxx-yyyy-zzzz"],
+            "summary": "Out of bounds\nread",
+            "severity": [
+                { "type": "CVSS_V4", "score": "CVSS:4.0/AV:N" },
+                { "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H" }
+            ],
+            "affected": [
+                {
+                    "package": { "ecosystem": "crates.io", "name": "foo" },
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{ "introduced": "0.0.0-0" }, { "fixed": "1.2.3" }, { "introduced": "2.0.0" }, { "fixed": "2.0.1" }]
+                    }]
+                },
+                {
+                    "package": { "ecosystem": "crates.io", "name": "foo-sys" },
+                    "ranges": [{ "type": "SEMVER", "events": [{ "fixed": "0.5.0" }] }]
+                }
+            ]
+        });
+
+        assert_eq!(
+            Advisory::from_value(&value, "foo"),
+            Some(Advisory {
+                id: "RUSTSEC-2024-0001".to_string(),
+                aliases: vec!["GHSA-xxxx-yyyy-zzzz".to_string()],
+                summary: Some("Out of bounds read".to_string()),
+                cvss: Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string()),
+                fixed_versions: vec!["1.2.3".to_string(), "2.0.1".to_string()],
+            })
+        );
+        assert_eq!(Advisory::from_value(&serde_json::json!({}), "foo"), None);
+    }
+
+    #[test]
+    fn test_crates_io_response_from_index() {
+        let body = [
+            r#"{"name":"serde","vers":"1.0.0","deps":[],"features":{"std":[]},"yanked":false}"#,
+            r#"{"name":"serde","vers":"1.1.0","deps":[],"features":{},"features2":{"derive":["dep:serde_derive"]},"yanked":false,"rust_version":"1.31","pubtime":"2024-01-01T00:00:00Z"}"#,
+            r#"{"name":"serde","vers":"1.2.0","deps":[],"features":{},"yanked":true}"#,
+            r#"{"name":"serde","vers":"2.0.0-beta","deps":[],"features":{},"yanked":false}"#,
+        ]
+        .join("\n");
+
+        let response = CratesIoResponse::from_index(&body, "1.0.0");
+        assert_eq!(response.latest_version, "1.1.0");
+        assert_eq!(
+            response.latest_version_date.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        assert_eq!(response.current_version_date, None);
+        assert_eq!(response.repository, None);
+        assert_eq!(
+            response
+                .versions
+                .iter()
+                .map(|v| v.num.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2.0.0-beta", "1.2.0", "1.1.0", "1.0.0"]
+        );
+        assert!(response.versions[1].yanked);
+        assert_eq!(response.versions[2].features, vec!["derive"]);
+        assert_eq!(response.versions[2].rust_version.as_deref(), Some("1.31"));
+
+        assert!(response.has_newer_version("1.0.0"));
+        assert!(response.has_newer_version("1.2.0"));
+        assert!(!CratesIoResponse::from_index(&body, "2.0.0-beta").has_newer_version("2.0.0-beta"));
+
+        assert_eq!(
+            CratesIoResponse::from_index("", "1.0.0").latest_version,
+            "1.0.0"
+        );
+        assert!(parse_index_response(200, body.as_bytes(), "1.0.0")
+            .unwrap()
+            .is_some());
+        assert!(parse_index_response(404, b"", "1.0.0").unwrap().is_none());
+        assert_eq!(
+            parse_index_response(429, b"", "1.0.0").err().as_deref(),
+            Some("returned 429")
+        );
+        assert_eq!(get_index_url("a"), "https://index.crates.io/1/a");
+        assert!(check_status("https://crates.io", 200).is_ok());
+        assert_eq!(
+            check_status("https://crates.io", 503).unwrap_err(),
+            "https://crates.io returned 503"
+        );
+        assert_eq!(get_index_url("Syn"), "https://index.crates.io/3/s/syn");
+        assert_eq!(
+            get_index_url("serde"),
+            "https://index.crates.io/se/rd/serde"
+        );
     }
 
     #[test]
@@ -153,5 +762,6 @@ mod tests {
         assert_eq!(response.latest_version, "0.1.0");
         assert_eq!(response.latest_version_date, None);
         assert_eq!(response.current_version_date, None);
+        assert!(response.versions.is_empty());
     }
 }
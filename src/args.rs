@@ -1,4 +1,14 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+
+use crate::{
+    dependency::{DependencyKind, Requirement, SortOrder, VerifyCommand},
+    git,
+    hooks::Hooks,
+    report::{FailOn, ReportFormat},
+    rules::Rule,
+    select::{self, Expression},
+    theme::{ColorChoice, Theme},
+};
 
 #[derive(Parser)]
 #[command(name = "cargo", bin_name = "cargo", styles = clap_cargo::style::CLAP_STYLING)]
@@ -9,6 +19,21 @@ pub enum CargoCli {
 #[derive(clap::Args)]
 #[command(version, about, author, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Log what is being done to stderr, use `-vv` for more details
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Suppress non-error output, only printing reports and diffs
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print the man page generated from these arguments and exit
+    #[arg(long)]
+    pub generate_man: bool,
+
     /// Selects all dependencies to be updated
     #[arg(short, long)]
     pub all: bool,
@@ -17,11 +42,463 @@ pub struct Args {
     #[arg(short, long)]
     pub yes: bool,
 
-    /// Don't run `cargo check` after updating
-    #[arg(short, long)]
+    /// Don't run `cargo check` after updating, same as `--verify none`
+    #[arg(short, long, conflicts_with = "verify")]
     pub no_check: bool,
 
-    /// Pin dependencies to exact versions, with an `=` prefix
-    #[arg(short, long)]
-    pub pin: bool,
+    /// Flag breaking updates of publicly re-exported crates and run `cargo semver-checks` against the last commit after the verification
+    #[arg(long)]
+    pub semver_checks: bool,
+
+    /// Offer to bump the Rust version pinned in rust-toolchain.toml to the latest stable release
+    #[arg(long)]
+    pub toolchain: bool,
+
+    /// Flag the dependencies never referenced in the sources of the package using them as possibly unused
+    #[arg(long)]
+    pub unused: bool,
+
+    /// Cargo command run after updating to verify the new versions, a failure is reflected in the exit code
+    #[arg(long, value_enum, default_value_t)]
+    pub verify: VerifyCommand,
+
+    /// Shell command run after the manifests are written, with the updated dependencies described in `CARGO_INTERACTIVE_UPDATE_*` environment variables
+    #[arg(long, value_name = "COMMAND")]
+    pub post_cmd: Option<String>,
+
+    /// Commit the changed Cargo.toml and Cargo.lock files after a successful update and verification
+    #[arg(long)]
+    pub commit: bool,
+
+    /// Update and commit each selected dependency separately, running the verification between each
+    #[arg(long, conflicts_with = "commit")]
+    pub commit_per_dep: bool,
+
+    /// Create or switch to a branch before updating, defaults to `deps/update-<date>`
+    #[arg(long, value_name = "NAME", num_args = 0..=1)]
+    pub branch: Option<Option<String>>,
+
+    /// Commit message of each dependency, with the `{crate}`, `{old}`, `{new}` and `{kind}` placeholders
+    #[arg(long, value_name = "TEMPLATE", default_value = git::DEFAULT_COMMIT_MESSAGE)]
+    pub commit_message: String,
+
+    /// Commit message title when committing multiple dependencies at once, with the `{count}` placeholder
+    #[arg(long, value_name = "TEMPLATE", default_value = git::DEFAULT_BATCH_COMMIT_MESSAGE)]
+    pub batch_commit_message: String,
+
+    /// Push the branch and open a pull request with `gh` after committing
+    #[arg(long, requires = "branch")]
+    pub open_pr: bool,
+
+    /// Don't add compare links and release note excerpts to the commit message and pull request body
+    #[arg(long)]
+    pub no_changelog: bool,
+
+    /// Move the selected dependencies declared by several members into [workspace.dependencies], the members inheriting them
+    #[arg(long)]
+    pub hoist: bool,
+
+    /// Copy each Cargo.toml to Cargo.toml.bak before writing it, or under the given directory
+    #[arg(long, value_name = "DIR", num_args = 0..=1)]
+    pub backup: Option<Option<std::path::PathBuf>>,
+
+    /// Print the unified diff of the Cargo.toml files instead of writing them, without opening the interactive list when used with `--all` or `--yes`
+    #[arg(long)]
+    pub diff: bool,
+
+    /// Only edit the Cargo.toml files, without running any cargo command that would update Cargo.lock
+    #[arg(long)]
+    pub no_lockfile: bool,
+
+    /// Don't generate a missing Cargo.lock for the current versions before updating
+    #[arg(long)]
+    pub no_generate_lockfile: bool,
+
+    /// Restore the original Cargo.toml files without asking when the verification fails
+    #[arg(long)]
+    pub rollback: bool,
+
+    /// Query crates.io for every dependency instead of reusing the responses of the last 10 minutes
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Only read the versions from the sparse index, fetching the dates, repository and description of a dependency once it is shown
+    #[arg(long)]
+    pub lazy: bool,
+
+    /// Don't record the applied updates in `.cargo-interactive-update/history.json`
+    #[arg(long)]
+    pub no_history: bool,
+
+    /// Requirement style of the updated versions, `--pin` alone pins to exact versions with an `=` prefix
+    #[arg(long, value_enum, value_name = "STYLE", num_args = 0..=1, default_missing_value = "exact")]
+    pub pin: Option<Requirement>,
+
+    /// Only check the dependencies of these workspace members
+    #[arg(short, long, value_name = "SPEC", value_delimiter = ',')]
+    pub package: Vec<String>,
+
+    /// When run from a workspace member's directory, only check that member instead of the whole workspace
+    #[arg(long, conflicts_with = "package")]
+    pub current_member: bool,
+
+    /// Check every workspace member instead of only the `default-members`
+    #[arg(long)]
+    pub workspace: bool,
+
+    /// Include pre-release versions when looking for the latest version
+    #[arg(long)]
+    pub pre_releases: bool,
+
+    /// Print the outdated dependencies in the given format instead of opening the interactive list
+    #[arg(long, value_enum)]
+    pub format: Option<ReportFormat>,
+
+    /// Print an aligned table of the outdated dependencies, same as `--format summary`
+    #[arg(long, conflicts_with_all = ["format", "porcelain"])]
+    pub summary: bool,
+
+    /// Print the outdated dependencies in a stable line-based format for scripts, same as `--format porcelain`
+    #[arg(long, conflicts_with = "format")]
+    pub porcelain: bool,
+
+    /// When the `--format`, `--porcelain` and `--check` modes exit with a non-zero code, `--check` defaults to `outdated` and the others to `none`
+    #[arg(long, value_enum, value_name = "WHEN")]
+    pub fail_on: Option<FailOn>,
+
+    /// Write the `--format`, `--porcelain` or `--check` report to this file, printing the summary on the console
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<std::path::PathBuf>,
+
+    /// Keep running and print the `--check` summary again whenever it changes, checking at this interval, e.g. `30m` or `1h`
+    #[arg(long, value_name = "INTERVAL", value_parser = parse_interval, conflicts_with_all = ["format", "yes", "precise", "diff"])]
+    pub watch: Option<std::time::Duration>,
+
+    /// Check every cargo project found under this directory, printing the `--check` summary of each
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["format", "yes", "precise", "diff", "watch", "package", "current_member"])]
+    pub recursive: Option<std::path::PathBuf>,
+
+    /// Print a summary and exit with 0 when up to date, 2 when only compatible updates are available and 3 when breaking updates are available
+    #[arg(long, conflicts_with_all = ["format", "yes"])]
+    pub check: bool,
+
+    /// Update a single dependency to the given version across the workspace, without asking for confirmation
+    #[arg(long, value_name = "CRATE@VERSION", value_parser = parse_precise, conflicts_with_all = ["format", "check"])]
+    pub precise: Option<(String, String)>,
+
+    /// When to use colors, `auto` disables them when the output isn't a terminal or `NO_COLOR` is set
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: ColorChoice,
+
+    /// Color scheme used by the interactive list
+    #[arg(long, value_enum, default_value_t)]
+    pub theme: Theme,
+
+    /// Order of the dependency sections, missing sections are appended in the default order
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = DependencyKind::ordered())]
+    pub section_order: Vec<DependencyKind>,
+
+    /// Sort order of the dependencies within each section
+    #[arg(long, value_enum, default_value_t)]
+    pub sort: SortOrder,
+
+    /// Apply the settings of a profile, either `safe`, `default`, `aggressive` or one defined in a configuration file
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Only check these crates, skipping the others before reaching crates.io
+    #[arg(long, value_delimiter = ',')]
+    pub only: Vec<String>,
+
+    /// Crates to skip entirely, without reaching crates.io
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Crates to hold back, they are still listed but can't be selected
+    #[arg(long, value_delimiter = ',')]
+    pub ignore: Vec<String>,
+
+    /// Hold back breaking updates, only allowing semver compatible ones
+    #[arg(long)]
+    pub compatible_only: bool,
+
+    /// Hold back versions released fewer than this many days ago
+    #[arg(long, value_name = "DAYS")]
+    pub min_age: Option<u64>,
+
+    /// Hold back versions requiring a newer Rust than the package's `rust-version`
+    #[arg(long)]
+    pub respect_rust_version: bool,
+
+    /// Hide dependencies listed only because their current version is yanked
+    #[arg(long, conflicts_with = "only_yanked")]
+    pub ignore_yanked: bool,
+
+    /// Only list dependencies whose current version is yanked
+    #[arg(long)]
+    pub only_yanked: bool,
+
+    /// Select the dependencies matching the expression, e.g. `kind==dev || magnitude==patch`
+    #[arg(long, value_name = "EXPRESSION", value_parser = select::parse)]
+    pub select: Option<Expression>,
+
+    /// Only select the updates fixing known security advisories, most severe first, and verify them with `cargo audit`
+    #[arg(long)]
+    pub security: bool,
+
+    #[arg(skip)]
+    pub rules: Vec<Rule>,
+
+    #[arg(skip)]
+    pub hooks: Hooks,
+
+    /// Pass `--locked` to the cargo commands run after updating
+    #[arg(long)]
+    pub locked: bool,
+
+    /// Pass `--frozen` to the cargo commands run after updating
+    #[arg(long)]
+    pub frozen: bool,
+
+    /// Pass `--offline` to the cargo commands run after updating
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(clap::Subcommand, Clone, PartialEq, Eq, Debug)]
+pub enum Command {
+    /// Print the updates previously applied in this project
+    History,
+    /// Revert the manifests and Cargo.lock to before the last applied updates
+    Rollback,
+    /// Explain which direct dependencies pull in a crate, holding back its version
+    Why {
+        /// Name of the crate, with `@<VERSION>` when multiple versions are used
+        #[arg(value_name = "CRATE")]
+        name: String,
+    },
+    /// Find the crates locked at multiple versions and select the updates that may converge them
+    Duplicates,
+    /// Print the versions and status of a single dependency as JSON, for editor integrations
+    Info {
+        /// Name of the dependency, as declared in Cargo.toml
+        #[arg(value_name = "CRATE")]
+        name: String,
+    },
+}
+
+impl Args {
+    pub fn report_format(&self) -> Option<ReportFormat> {
+        if self.porcelain {
+            Some(ReportFormat::Porcelain)
+        } else if self.summary {
+            Some(ReportFormat::Summary)
+        } else {
+            self.format
+        }
+    }
+
+    pub fn resolve_paths(&mut self, dir: &std::path::Path) {
+        if let Some(output) = &mut self.output {
+            *output = dir.join(&*output);
+        }
+
+        if let Some(Some(backup)) = &mut self.backup {
+            *backup = dir.join(&*backup);
+        }
+    }
+
+    pub fn verify_command(&self) -> VerifyCommand {
+        if self.no_check {
+            VerifyCommand::None
+        } else if self.security {
+            VerifyCommand::Audit
+        } else {
+            self.verify
+        }
+    }
+
+    pub fn cargo_flags(&self) -> Vec<&'static str> {
+        [
+            (self.locked, "--locked"),
+            (self.frozen, "--frozen"),
+            (self.offline, "--offline"),
+            (self.quiet, "--quiet"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect()
+    }
+}
+
+// Same values as when no arguments are given on the command line
+impl Default for Args {
+    fn default() -> Self {
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update"]);
+        args
+    }
+}
+
+pub fn get_man_page() -> Result<Vec<u8>, std::io::Error> {
+    let command = CargoCli::command()
+        .find_subcommand("interactive-update")
+        .cloned()
+        .expect("The interactive-update subcommand is always defined")
+        .name("cargo-interactive-update");
+
+    let mut man_page = vec![];
+    clap_mangen::Man::new(command).render(&mut man_page)?;
+
+    Ok(man_page)
+}
+
+fn parse_precise(value: &str) -> Result<(String, String), String> {
+    let (name, version) = value
+        .split_once('@')
+        .filter(|(name, version)| !name.is_empty() && !version.is_empty())
+        .ok_or_else(|| "expected <CRATE>@<VERSION>".to_string())?;
+
+    semver::Version::parse(version).map_err(|e| format!("invalid version `{version}`: {e}"))?;
+
+    Ok((name.to_string(), version.to_string()))
+}
+
+// A number of seconds, or of minutes, hours or days with the `m`, `h` or `d` suffix
+fn parse_interval(value: &str) -> Result<std::time::Duration, String> {
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((index, 's')) => (&value[..index], 1),
+        Some((index, 'm')) => (&value[..index], 60),
+        Some((index, 'h')) => (&value[..index], 3_600),
+        Some((index, 'd')) => (&value[..index], 86_400),
+        _ => (value, 1),
+    };
+
+    let seconds = number
+        .parse::<u64>()
+        .ok()
+        .filter(|seconds| *seconds > 0)
+        .ok_or_else(|| format!("invalid interval `{value}`, expected e.g. `90`, `30m` or `1h`"))?;
+
+    Ok(std::time::Duration::from_secs(seconds * multiplier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_flags() {
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update"]);
+        assert!(args.cargo_flags().is_empty());
+
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update", "--locked", "--offline"]);
+        assert_eq!(args.cargo_flags(), vec!["--locked", "--offline"]);
+
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update", "-q", "--frozen"]);
+        assert_eq!(args.cargo_flags(), vec!["--frozen", "--quiet"]);
+    }
+
+    #[test]
+    fn test_resolve_paths() {
+        let CargoCli::InteractiveUpdate(mut args) = CargoCli::parse_from([
+            "cargo",
+            "interactive-update",
+            "--output",
+            "report.json",
+            "--backup",
+            "/tmp/backups",
+        ]);
+        args.resolve_paths(std::path::Path::new("crates/foo"));
+
+        assert_eq!(
+            args.output,
+            Some(std::path::PathBuf::from("crates/foo/report.json"))
+        );
+        assert_eq!(
+            args.backup,
+            Some(Some(std::path::PathBuf::from("/tmp/backups")))
+        );
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90"), Ok(std::time::Duration::from_secs(90)));
+        assert_eq!(
+            parse_interval("90s"),
+            Ok(std::time::Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_interval("30m"),
+            Ok(std::time::Duration::from_secs(1_800))
+        );
+        assert_eq!(
+            parse_interval("1h"),
+            Ok(std::time::Duration::from_secs(3_600))
+        );
+        assert_eq!(
+            parse_interval("2d"),
+            Ok(std::time::Duration::from_secs(172_800))
+        );
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("m").is_err());
+        assert!(parse_interval("1w").is_err());
+    }
+
+    #[test]
+    fn test_parse_precise() {
+        assert_eq!(
+            parse_precise("serde@1.0.200"),
+            Ok(("serde".to_string(), "1.0.200".to_string()))
+        );
+        assert!(parse_precise("serde").is_err());
+        assert!(parse_precise("@1.0.0").is_err());
+        assert!(parse_precise("serde@latest").is_err());
+    }
+
+    #[test]
+    fn test_verify_command() {
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update"]);
+        assert_eq!(args.verify_command(), VerifyCommand::Check);
+
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update", "--verify", "test"]);
+        assert_eq!(args.verify_command(), VerifyCommand::Test);
+
+        let CargoCli::InteractiveUpdate(args) =
+            CargoCli::parse_from(["cargo", "interactive-update", "-n"]);
+        assert_eq!(args.verify_command(), VerifyCommand::None);
+
+        assert_eq!(Args::default().verify_command(), VerifyCommand::Check);
+    }
+
+    #[test]
+    fn test_get_man_page() {
+        let man_page = String::from_utf8(get_man_page().unwrap()).unwrap();
+
+        assert!(man_page.starts_with(".ie \\n(.g .ds Aq \\(aq"));
+        assert!(man_page.contains(".TH cargo-interactive-update 1"));
+        assert!(man_page.contains("\\-\\-pre\\-releases"));
+    }
+
+    #[test]
+    fn test_pin() {
+        let parse = |extra_args: &[&str]| {
+            let CargoCli::InteractiveUpdate(args) = CargoCli::parse_from(
+                ["cargo", "interactive-update"]
+                    .iter()
+                    .chain(extra_args.iter()),
+            );
+            args.pin
+        };
+
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&["--pin"]), Some(Requirement::Exact));
+        assert_eq!(parse(&["--pin=tilde"]), Some(Requirement::Tilde));
+        assert_eq!(parse(&["--pin", "caret"]), Some(Requirement::Caret));
+    }
 }
@@ -0,0 +1,192 @@
+use serde_json::{json, Map, Value};
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    api::{CratesIoResponse, RegistryVersion},
+    cargo::CargoDependency,
+    logger,
+};
+
+// Long enough for the repeated runs of an update session, short enough to notice new releases
+const MAX_AGE_SECONDS: u64 = 10 * 60;
+
+// The crates.io responses of the previous runs, keyed by crate and declared version since the
+// date of the current version depends on it
+#[derive(Default)]
+pub struct Cache {
+    entries: Map<String, Value>,
+    now: u64,
+}
+
+impl Cache {
+    pub fn load() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let entries = get_cache_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { entries, now }
+    }
+
+    pub fn get(&self, dependency: &CargoDependency) -> Option<CratesIoResponse> {
+        let entry = self.entries.get(&get_key(dependency))?;
+        let fetched_at = entry.get("fetched_at")?.as_u64()?;
+        if self.now.saturating_sub(fetched_at) > MAX_AGE_SECONDS {
+            return None;
+        }
+
+        response_from_value(entry.get("response")?)
+    }
+
+    pub fn insert(&mut self, dependency: &CargoDependency, response: &CratesIoResponse) {
+        self.entries.insert(
+            get_key(dependency),
+            json!({
+                "fetched_at": self.now,
+                "response": response_to_value(response),
+            }),
+        );
+    }
+
+    // Stale entries are dropped so the file doesn't grow with every crate ever looked up
+    pub fn save(mut self) {
+        let now = self.now;
+        self.entries.retain(|_, entry| {
+            entry
+                .get("fetched_at")
+                .and_then(|fetched_at| fetched_at.as_u64())
+                .is_some_and(|fetched_at| now.saturating_sub(fetched_at) <= MAX_AGE_SECONDS)
+        });
+
+        let Some(path) = get_cache_path() else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, Value::Object(self.entries).to_string()));
+        if let Err(e) = result {
+            logger::info(format!("Unable to write the cache {}: {e}", path.display()));
+        }
+    }
+}
+
+fn get_cache_path() -> Option<PathBuf> {
+    let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| Path::new(&home).join(".cache"))
+        })?;
+
+    Some(
+        cache_dir
+            .join("cargo-interactive-update")
+            .join("crates.json"),
+    )
+}
+
+fn get_key(dependency: &CargoDependency) -> String {
+    format!("{}@{}", dependency.crate_name(), dependency.version)
+}
+
+fn response_to_value(response: &CratesIoResponse) -> Value {
+    json!({
+        "repository": response.repository,
+        "description": response.description,
+        "latest_version": response.latest_version,
+        "latest_version_date": response.latest_version_date,
+        "current_version_date": response.current_version_date,
+        "versions": response.versions.iter().map(|version| json!({
+            "num": version.num,
+            "updated_at": version.updated_at,
+            "yanked": version.yanked,
+            "rust_version": version.rust_version,
+            "features": version.features,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn response_from_value(value: &Value) -> Option<CratesIoResponse> {
+    let string = |value: &Value, key| value.get(key)?.as_str().map(str::to_string);
+
+    Some(CratesIoResponse {
+        repository: string(value, "repository"),
+        description: string(value, "description"),
+        latest_version: string(value, "latest_version")?,
+        latest_version_date: string(value, "latest_version_date"),
+        current_version_date: string(value, "current_version_date"),
+        versions: value
+            .get("versions")?
+            .as_array()?
+            .iter()
+            .map(|version| {
+                Some(RegistryVersion {
+                    num: string(version, "num")?,
+                    updated_at: string(version, "updated_at"),
+                    yanked: version.get("yanked")?.as_bool()?,
+                    rust_version: string(version, "rust_version"),
+                    features: version
+                        .get("features")?
+                        .as_array()?
+                        .iter()
+                        .filter_map(|feature| feature.as_str().map(str::to_string))
+                        .collect(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let dependency = CargoDependency {
+            name: "serde".to_string(),
+            version: "1.0.100".to_string(),
+            ..Default::default()
+        };
+        let response = CratesIoResponse {
+            repository: Some("https://github.com/serde-rs/serde".to_string()),
+            description: None,
+            latest_version: "1.0.210".to_string(),
+            latest_version_date: Some("2024-09-06T20:00:00Z".to_string()),
+            current_version_date: None,
+            versions: vec![RegistryVersion {
+                num: "1.0.210".to_string(),
+                features: vec!["derive".to_string()],
+                ..Default::default()
+            }],
+        };
+        let mut cache = Cache {
+            now: 1_000_000,
+            ..Default::default()
+        };
+
+        assert!(cache.get(&dependency).is_none());
+        cache.insert(&dependency, &response);
+        let cached = cache.get(&dependency).unwrap();
+        assert_eq!(cached.latest_version, "1.0.210");
+        assert_eq!(cached.repository, response.repository);
+        assert_eq!(cached.versions, response.versions);
+
+        let other_version = CargoDependency {
+            version: "1.0.200".to_string(),
+            ..dependency.clone()
+        };
+        assert!(cache.get(&other_version).is_none());
+
+        cache.now += MAX_AGE_SECONDS + 1;
+        assert!(cache.get(&dependency).is_none());
+    }
+}
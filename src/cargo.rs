@@ -1,10 +1,16 @@
 use semver::Version;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 use toml_edit::{DocumentMut, Item, Value};
 
 use crate::{
     api,
+    cache::Cache,
     dependency::{Dependencies, Dependency, DependencyKind},
+    error::Error,
+    logger, pool,
 };
 
 #[derive(Clone, PartialEq, Eq, Default)]
@@ -12,107 +18,405 @@ pub struct CargoDependency {
     pub name: String,
     pub version: String,
     pub kind: DependencyKind,
+    pub package: Option<String>,
+    pub registry: Option<String>,
+}
+
+// How the latest versions are looked up
+#[derive(Clone, Copy, Default)]
+pub struct Retrieval {
+    pub use_cache: bool,
+    // Only the version lists are read from the index, the crate details being fetched on demand
+    pub lazy: bool,
 }
 
 impl CargoDependency {
-    fn get_latest_version_wrapper(
-        &self,
+    pub fn crate_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    fn into_dependency(
+        self,
+        response: api::CratesIoResponse,
         package_name: Option<String>,
         workspace_path: Option<String>,
-    ) -> Option<Dependency> {
-        let parsed_current_version = Version::parse(&self.version).ok()?;
-
-        let response = api::get_latest_version(self).expect("Unable to reach crates.io");
-
-        let parsed_latest_version =
-            Version::parse(&response.latest_version).expect("Latest version is not a valid semver");
-
-        if parsed_current_version < parsed_latest_version {
-            Some(Dependency {
-                name: self.name.to_string(),
-                current_version: self.version.to_string(),
-                latest_version: response.latest_version,
-                repository: response.repository,
-                latest_version_date: response.latest_version_date,
-                current_version_date: response.current_version_date,
-                description: response.description,
-                kind: self.kind,
-                package_name,
-                workspace_path,
-            })
-        } else {
-            None
+    ) -> Dependency {
+        Dependency {
+            name: self.name.to_string(),
+            package: self.package.clone(),
+            current_version: self.version.to_string(),
+            latest_version: response.latest_version,
+            repository: response.repository,
+            latest_version_date: response.latest_version_date,
+            current_version_date: response.current_version_date,
+            description: response.description,
+            kind: self.kind,
+            package_name,
+            workspace_path,
+            requirement: Default::default(),
+            versions: response.versions,
+            workspace_users: vec![],
+            held_back: None,
+            reexported: false,
+            possibly_unused: false,
+            group: None,
+            auto_select: None,
+            features: None,
+            advisories: vec![],
+            details_pending: false,
+            unknown_source: None,
         }
     }
 }
 
-#[derive(Clone, Default)]
+// The crates.io responses in the order of the dependencies, multiplexed when possible
+pub fn get_latest_versions(
+    dependencies: Vec<&CargoDependency>,
+) -> Vec<Result<api::CratesIoResponse, String>> {
+    if dependencies.is_empty() {
+        vec![]
+    } else if api::supports_multiplexing() {
+        api::get_latest_versions(&dependencies)
+    } else {
+        pool::map(dependencies, |dependency| {
+            api::get_latest_version(dependency).map_err(|e| e.to_string())
+        })
+    }
+}
+
+// Printed along the other warnings once the run is over, a single line however many crates
+fn warn_unknown_crates(lookups: &[&Lookup]) {
+    let mut names = lookups
+        .iter()
+        .map(|lookup| format!("`{}`", lookup.dependency.crate_name()))
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    match names.as_slice() {
+        [] => {}
+        [name] => logger::warn(format!(
+            "{name} wasn't found on crates.io, it is listed as held back from an unknown source"
+        )),
+        names => logger::warn(format!(
+            "{} weren't found on crates.io, they are listed as held back from an unknown source",
+            names.join(", ")
+        )),
+    }
+}
+
+struct Lookup {
+    dependency: CargoDependency,
+    package_name: Option<String>,
+    workspace_path: Option<String>,
+    workspace_users: Vec<String>,
+}
+
+impl Lookup {
+    fn into_dependency(
+        self,
+        response: Result<api::CratesIoResponse, String>,
+    ) -> Result<Dependency, Error> {
+        let response = response.map_err(|message| Error::Network {
+            crate_name: self.dependency.crate_name().to_string(),
+            message,
+        })?;
+
+        Ok(Dependency {
+            workspace_users: self.workspace_users,
+            ..self
+                .dependency
+                .into_dependency(response, self.package_name, self.workspace_path)
+        })
+    }
+}
+
+/// The dependencies declared in the manifests, before looking them up on crates.io
+#[derive(Default)]
 pub struct CargoDependencies {
     pub cargo_toml: DocumentMut,
-    package_name: String,
+    package_name: Option<String>,
     dependencies: Vec<CargoDependency>,
     workspace_members: HashMap<String, Box<CargoDependencies>>,
+    default_members: Option<Vec<String>>,
 }
 
 impl CargoDependencies {
-    pub fn gather_dependencies(relative_path: &str) -> Self {
-        let cargo_toml = read_cargo_file(relative_path);
+    pub fn gather_dependencies(relative_path: &str) -> Result<Self, Error> {
+        let cargo_toml = read_cargo_file(relative_path)?;
+        let workspace_members = get_workspace_members(&cargo_toml, Path::new(relative_path));
+        let default_members =
+            get_workspace_default_member_paths(&cargo_toml, Path::new(relative_path));
+
+        Ok(Self {
+            default_members,
+            ..Self::from_cargo_toml(cargo_toml, workspace_members)
+        })
+    }
+
+    fn from_cargo_toml(
+        cargo_toml: DocumentMut,
+        workspace_members: HashMap<String, Box<CargoDependencies>>,
+    ) -> Self {
         let package_name = get_package_name(&cargo_toml);
         let dependencies = get_cargo_dependencies(&cargo_toml);
-        let workspace_members = get_workspace_members(&cargo_toml);
 
         Self {
             cargo_toml,
             package_name,
             dependencies,
             workspace_members,
+            default_members: None,
         }
     }
 
-    pub fn retrieve_outdated_dependencies(self, workspace_path: Option<String>) -> Dependencies {
-        let mut direct_dependencies_threads = Vec::new();
-        let mut workspace_member_threads = Vec::new();
+    // The dependencies of the root and of every member are fetched together, multiplexed over
+    // HTTP/2 when libcurl supports it and by the worker pool otherwise. The responses of the
+    // previous runs are reused when still fresh, unless `use_cache` is false
+    pub fn retrieve_dependencies(
+        self,
+        workspace_path: Option<String>,
+        retrieval: Retrieval,
+    ) -> Result<Dependencies, Error> {
+        let mut lookups = vec![];
         let mut cargo_toml_files = HashMap::new();
+        self.collect_lookups(workspace_path, &mut lookups, &mut cargo_toml_files);
+        lookups.retain(|lookup| Version::parse(&lookup.dependency.version).is_ok());
+
+        // A crate of another registry may share its name with an unrelated crates.io one, so
+        // neither is it looked up nor read from the cache
+        let mut unknown_sources = lookups
+            .iter()
+            .map(|lookup| {
+                let registry = lookup.dependency.registry.as_ref()?;
+                Some(format!("published to the `{registry}` registry"))
+            })
+            .collect::<Vec<_>>();
+
+        let mut cache = Cache::load();
+        let mut responses = lookups
+            .iter()
+            .zip(&unknown_sources)
+            .map(|(lookup, unknown_source)| {
+                (retrieval.use_cache && unknown_source.is_none())
+                    .then(|| cache.get(&lookup.dependency))
+                    .flatten()
+                    .map(Ok)
+            })
+            .collect::<Vec<_>>();
+        let missing = (0..lookups.len())
+            .filter(|i| responses[*i].is_none() && unknown_sources[*i].is_none())
+            .collect::<Vec<_>>();
+        logger::debug(format!(
+            "{} of the {} crates are cached",
+            lookups.len() - missing.len(),
+            lookups.len()
+        ));
+
+        let missing_dependencies = missing
+            .iter()
+            .map(|i| &lookups[*i].dependency)
+            .collect::<Vec<_>>();
+        // Most crates are usually up to date, which the index listings tell at a fraction of the
+        // cost of the API, only the outdated ones then being looked up for their details
+        let fetched = pool::map(missing_dependencies, |dependency| {
+            api::get_index_versions(dependency).map_err(|e| e.to_string())
+        });
+        let mut details_pending = vec![false; lookups.len()];
+        let mut outdated = vec![];
+        let mut unknown = vec![];
+        for (i, response) in missing.into_iter().zip(fetched) {
+            let response = match response {
+                Ok(Some(response)) => Ok(response),
+                Ok(None) => {
+                    unknown_sources[i] = Some("not found on crates.io".to_string());
+                    unknown.push(i);
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+            if response
+                .as_ref()
+                .is_ok_and(|response| response.has_newer_version(&lookups[i].dependency.version))
+            {
+                outdated.push(i);
+            }
+            details_pending[i] = true;
+            responses[i] = Some(response);
+        }
+        if !retrieval.lazy {
+            logger::debug(format!(
+                "{} of the crates have a newer version",
+                outdated.len()
+            ));
+            let outdated_dependencies = outdated
+                .iter()
+                .map(|i| &lookups[*i].dependency)
+                .collect::<Vec<_>>();
+            // A failed lookup keeps the listing, its details being retried once needed
+            for (i, response) in outdated
+                .into_iter()
+                .zip(get_latest_versions(outdated_dependencies))
+            {
+                if let Ok(response) = response {
+                    cache.insert(&lookups[i].dependency, &response);
+                    details_pending[i] = false;
+                    responses[i] = Some(Ok(response));
+                }
+            }
+        }
+        cache.save();
+
+        warn_unknown_crates(&unknown.iter().map(|i| &lookups[*i]).collect::<Vec<_>>());
+
+        let mut dependencies = vec![];
+        for (((lookup, response), details_pending), unknown_source) in lookups
+            .into_iter()
+            .zip(responses)
+            .zip(details_pending)
+            .zip(unknown_sources)
+        {
+            // Crates from an unknown source are listed without any newer version, held back so
+            // they can't be selected
+            let response = response
+                .unwrap_or_else(|| Ok(api::CratesIoResponse::empty(&lookup.dependency.version)));
+            dependencies.push(Dependency {
+                details_pending,
+                held_back: unknown_source
+                    .as_ref()
+                    .map(|reason| format!("unknown source, {reason}")),
+                unknown_source,
+                ..lookup.into_dependency(response)?
+            });
+        }
+
+        dependencies.sort();
+
+        Ok(Dependencies::new(dependencies, cargo_toml_files))
+    }
+
+    fn collect_lookups(
+        self,
+        workspace_path: Option<String>,
+        lookups: &mut Vec<Lookup>,
+        cargo_toml_files: &mut HashMap<String, DocumentMut>,
+    ) {
+        for dependency in self.dependencies {
+            let workspace_users = if dependency.kind == DependencyKind::Workspace {
+                get_workspace_dependency_users(&self.workspace_members, &dependency.name)
+            } else {
+                vec![]
+            };
+            lookups.push(Lookup {
+                dependency,
+                package_name: self.package_name.clone(),
+                workspace_path: workspace_path.clone(),
+                workspace_users,
+            });
+        }
 
         cargo_toml_files.insert(
-            workspace_path.clone().unwrap_or_else(|| ".".to_string()),
+            workspace_path.unwrap_or_else(|| ".".to_string()),
             self.cargo_toml,
         );
-        for dependency in self.dependencies.iter() {
-            let dependency = dependency.clone();
-            let package_name = self.package_name.to_string();
-            let workspace_path = workspace_path.clone();
-            direct_dependencies_threads.push(std::thread::spawn(move || {
-                dependency.get_latest_version_wrapper(Some(package_name), workspace_path)
-            }));
+        for (member, dependencies) in self.workspace_members {
+            dependencies.collect_lookups(Some(member), lookups, cargo_toml_files);
         }
+    }
+
+    pub fn filter(&mut self, only: &[String], exclude: &[String]) {
+        self.dependencies.retain(|dependency| {
+            (only.is_empty() || only.contains(&dependency.name))
+                && !exclude.contains(&dependency.name)
+        });
 
-        for (member, dependencies) in self.workspace_members.iter() {
-            let dependencies = dependencies.clone();
-            let member = member.clone();
-            workspace_member_threads.push(std::thread::spawn(move || {
-                dependencies.retrieve_outdated_dependencies(Some(member))
-            }));
+        for dependencies in self.workspace_members.values_mut() {
+            dependencies.filter(only, exclude);
         }
+    }
 
-        let mut dependencies = direct_dependencies_threads
-            .into_iter()
-            .flat_map(|t| t.join())
-            .flatten()
-            .collect::<Vec<_>>();
+    // Renamed dependencies are matched by the name of the crate they point to
+    pub fn filter_crate(&mut self, crate_name: &str) {
+        self.dependencies.retain(|dependency| {
+            dependency.package.as_deref().unwrap_or(&dependency.name) == crate_name
+        });
 
-        workspace_member_threads
-            .into_iter()
-            .for_each(|workspace_dependencies| {
-                let _ = workspace_dependencies.join().map(|workspace_dependencies| {
-                    dependencies.extend(workspace_dependencies.dependencies);
-                    cargo_toml_files.extend(workspace_dependencies.cargo_toml_files);
-                });
-            });
+        for dependencies in self.workspace_members.values_mut() {
+            dependencies.filter_crate(crate_name);
+        }
+    }
 
-        dependencies.sort();
+    pub fn filter_packages(&mut self, packages: &[String]) -> Result<(), Error> {
+        if packages.is_empty() {
+            return Ok(());
+        }
 
-        Dependencies::new(dependencies, cargo_toml_files)
+        if let Some(unknown) = packages.iter().find(|package| {
+            self.package_name.as_ref() != Some(*package)
+                && !self
+                    .workspace_members
+                    .values()
+                    .any(|member| member.package_name.as_ref() == Some(*package))
+        }) {
+            return Err(Error::UnknownPackage(unknown.to_string()));
+        }
+
+        self.workspace_members.retain(|_, member| {
+            member
+                .package_name
+                .as_ref()
+                .is_some_and(|name| packages.contains(name))
+        });
+
+        let is_root_selected = self
+            .package_name
+            .as_ref()
+            .is_some_and(|name| packages.contains(name));
+        self.retain_root_dependencies(is_root_selected);
+
+        Ok(())
+    }
+
+    pub fn filter_default_members(&mut self) {
+        let Some(default_members) = self.default_members.take() else {
+            return;
+        };
+
+        self.workspace_members
+            .retain(|path, _| default_members.contains(path));
+        self.retain_root_dependencies(default_members.iter().any(|path| path == "."));
+    }
+
+    pub fn filter_member(&mut self, member: &str) {
+        self.workspace_members.retain(|path, _| path == member);
+        self.retain_root_dependencies(false);
+    }
+
+    fn retain_root_dependencies(&mut self, is_root_selected: bool) {
+        let workspace_members = &self.workspace_members;
+        self.dependencies.retain(|dependency| {
+            is_root_selected
+                || (dependency.kind == DependencyKind::Workspace
+                    && !get_workspace_dependency_users(workspace_members, &dependency.name)
+                        .is_empty())
+        });
+    }
+
+    // Every declared dependency along with the path of the workspace member declaring it
+    pub fn get_declared_dependencies(&self) -> Vec<(Option<&str>, &CargoDependency)> {
+        let mut workspace_paths = self.workspace_members.keys().collect::<Vec<_>>();
+        workspace_paths.sort();
+
+        self.dependencies
+            .iter()
+            .map(|dependency| (None, dependency))
+            .chain(workspace_paths.into_iter().flat_map(|workspace_path| {
+                self.workspace_members[workspace_path]
+                    .dependencies
+                    .iter()
+                    .map(|dependency| (Some(workspace_path.as_str()), dependency))
+            }))
+            .collect()
     }
 
     pub fn len(&self) -> usize {
@@ -122,21 +426,132 @@ impl CargoDependencies {
                 .values()
                 .fold(0, |acc, deps| acc + deps.len())
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
-fn read_cargo_file(relative_path: &str) -> DocumentMut {
-    let cargo_toml_content = std::fs::read_to_string(format!("{relative_path}/Cargo.toml"))
-        .unwrap_or_else(|e| {
-            eprintln!("Unable to read Cargo.toml file: {}", e);
-            String::new()
-        });
+// Like cargo, the root is either the one pointed at by `package.workspace` or the closest parent
+// manifest declaring a `[workspace]`, as long as the directory is one of its members, returning
+// the root and the member's path relative to it
+pub fn find_workspace_root(dir: &Path) -> Option<(PathBuf, String)> {
+    let cargo_toml = read_manifest(dir)?;
+    if cargo_toml.contains_key("workspace") {
+        return None;
+    }
+
+    if let Some(root) = cargo_toml
+        .get("package")
+        .and_then(|package| package.get("workspace"))
+        .and_then(|workspace| workspace.as_str())
+    {
+        let root = std::fs::canonicalize(dir.join(root)).ok()?;
+        let dir = std::fs::canonicalize(dir).ok()?;
+        return get_workspace_member(&root, &dir);
+    }
+
+    dir.ancestors()
+        .skip(1)
+        .find(|root| read_manifest(root).is_some_and(|root| root.contains_key("workspace")))
+        .and_then(|root| get_workspace_member(root, dir))
+}
+
+// Every package or workspace root under the directory, skipping the members of a workspace as
+// they are checked along with it
+pub fn find_projects(dir: &Path) -> Vec<PathBuf> {
+    let mut projects = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        if dir.join("Cargo.toml").is_file() && !is_workspace_member(&dir) {
+            projects.push(dir.clone());
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        dirs.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name != "target" && !name.starts_with('.'))
+                }),
+        );
+    }
+
+    projects.sort();
+    projects
+}
+
+fn is_workspace_member(dir: &Path) -> bool {
+    match read_manifest(dir) {
+        Some(_) => find_workspace_root(dir).is_some(),
+        // Manifests that can't be parsed are reported with their workspace when they belong to one
+        None => dir
+            .ancestors()
+            .skip(1)
+            .find(|root| read_manifest(root).is_some_and(|root| root.contains_key("workspace")))
+            .and_then(|root| get_workspace_member(root, dir))
+            .is_some(),
+    }
+}
+
+fn get_workspace_member(root: &Path, dir: &Path) -> Option<(PathBuf, String)> {
+    let member = dir
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    get_workspace_member_paths(&read_manifest(root)?, root)
+        .contains(&member)
+        .then(|| (root.to_path_buf(), member))
+}
+
+fn read_manifest(dir: &Path) -> Option<DocumentMut> {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn read_cargo_file(relative_path: &str) -> Result<DocumentMut, Error> {
+    let path = format!("{relative_path}/Cargo.toml");
+    logger::debug(format!("Reading {path}"));
+
+    let cargo_toml_content = std::fs::read_to_string(&path).map_err(|e| Error::ManifestRead {
+        path: path.clone(),
+        message: e.to_string(),
+    })?;
 
     cargo_toml_content
         .parse()
-        .expect("Unable to parse Cargo.toml file as TOML")
+        .map_err(|e: toml_edit::TomlError| Error::ManifestParse {
+            path,
+            message: e.to_string(),
+        })
 }
 
 fn get_cargo_dependencies(cargo_toml: &DocumentMut) -> Vec<CargoDependency> {
+    let workspace_dependencies = extract_dependencies_from_sections(
+        cargo_toml
+            .get("workspace")
+            .and_then(|w| w.get("dependencies")),
+        DependencyKind::Workspace,
+    );
+
+    // Virtual workspace roots only declare `[workspace.dependencies]`
+    if !cargo_toml.contains_key("package") {
+        return workspace_dependencies;
+    }
+
     let dependencies =
         extract_dependencies_from_sections(cargo_toml.get("dependencies"), DependencyKind::Normal);
 
@@ -148,13 +563,6 @@ fn get_cargo_dependencies(cargo_toml: &DocumentMut) -> Vec<CargoDependency> {
         DependencyKind::Build,
     );
 
-    let workspace_dependencies = extract_dependencies_from_sections(
-        cargo_toml
-            .get("workspace")
-            .and_then(|w| w.get("dependencies")),
-        DependencyKind::Workspace,
-    );
-
     dependencies
         .into_iter()
         .chain(dev_dependencies)
@@ -178,10 +586,46 @@ fn extract_dependencies_from_sections(
     package_deps
         .iter()
         .flat_map(|(name, package_data)| {
-            let version = match package_data {
-                Item::Value(Value::String(v)) => v.value().to_string(),
-                Item::Value(Value::InlineTable(t)) => t.get("version")?.as_str()?.to_string(),
-                Item::Table(t) => t.get("version")?.as_str()?.to_string(),
+            // The version of git dependencies only constrains the checked out revision, so
+            // rewriting it from the registry would break their resolution. Listing the ones
+            // declaring a version tells them apart from the up to date dependencies.
+            if package_data.get("git").is_some() {
+                match package_data.get("version").and_then(|version| version.as_str()) {
+                    Some(version) => logger::warn(format!(
+                        "`{name}` has a git source, its version {version} only constrains the checked out revision and isn't updated"
+                    )),
+                    None => logger::debug(format!("Skipping {name}, it is a git dependency")),
+                }
+                return None;
+            }
+
+            // Inherited dependencies are updated in `[workspace.dependencies]`, a local version
+            // next to `workspace = true` isn't used by cargo and must not be written either
+            if package_data
+                .get("workspace")
+                .and_then(|workspace| workspace.as_bool())
+                .unwrap_or(false)
+            {
+                if package_data.get("version").is_some() {
+                    logger::warn(format!(
+                        "Ignoring the version of `{name}`, it is inherited from [workspace.dependencies]"
+                    ));
+                }
+                return None;
+            }
+
+            let (version, package, registry) = match package_data {
+                Item::Value(Value::String(v)) => (v.value().to_string(), None, None),
+                Item::Value(Value::InlineTable(t)) => (
+                    t.get("version")?.as_str()?.to_string(),
+                    t.get("package").and_then(|p| p.as_str()),
+                    t.get("registry").and_then(|r| r.as_str()),
+                ),
+                Item::Table(t) => (
+                    t.get("version")?.as_str()?.to_string(),
+                    t.get("package").and_then(|p| p.as_str()),
+                    t.get("registry").and_then(|r| r.as_str()),
+                ),
                 _ => return None,
             };
 
@@ -189,42 +633,173 @@ fn extract_dependencies_from_sections(
                 name: name.to_string(),
                 version,
                 kind,
+                package: package.map(|package| package.to_string()),
+                registry: registry.map(|registry| registry.to_string()),
             })
         })
         .collect()
 }
 
-fn get_workspace_members(cargo_toml: &DocumentMut) -> HashMap<String, Box<CargoDependencies>> {
-    let Some(workspace_members) = cargo_toml
+// Members declaring their own `[workspace]` are the root of a separate workspace, which cargo
+// doesn't allow to be nested, so they are skipped instead of being read as part of this one
+fn get_workspace_members(
+    cargo_toml: &DocumentMut,
+    root: &Path,
+) -> HashMap<String, Box<CargoDependencies>> {
+    // Large workspaces have hundreds of manifests, read and parsed in parallel while the
+    // warnings keep the order of the members
+    let manifests = pool::map(get_workspace_member_paths(cargo_toml, root), |member| {
+        let cargo_toml = read_cargo_file(&root.join(&member).to_string_lossy());
+        (member, cargo_toml)
+    });
+
+    manifests
+        .into_iter()
+        .filter_map(|(member, cargo_toml)| {
+            // Unreadable members are still listed, without dependencies, as they always were
+            let cargo_toml = match cargo_toml {
+                Ok(cargo_toml) => cargo_toml,
+                Err(e @ Error::ManifestRead { .. }) => {
+                    logger::warn(e.to_string());
+                    DocumentMut::new()
+                }
+                Err(e) => {
+                    logger::warn(format!("Skipping workspace member `{member}`, {e}"));
+                    return None;
+                }
+            };
+            if cargo_toml.contains_key("workspace") {
+                logger::warn(format!(
+                    "Skipping workspace member `{member}`, it declares its own [workspace]"
+                ));
+                return None;
+            }
+
+            let dependencies = CargoDependencies::from_cargo_toml(cargo_toml, HashMap::new());
+            Some((member, Box::new(dependencies)))
+        })
+        .collect()
+}
+
+// Like cargo, glob patterns only match directories containing a manifest and not excluded
+fn get_workspace_member_paths(cargo_toml: &DocumentMut, root: &Path) -> Vec<String> {
+    get_workspace_paths(cargo_toml, "members", root).unwrap_or_default()
+}
+
+fn get_workspace_default_member_paths(
+    cargo_toml: &DocumentMut,
+    root: &Path,
+) -> Option<Vec<String>> {
+    get_workspace_paths(cargo_toml, "default-members", root)
+}
+
+fn get_workspace_paths(cargo_toml: &DocumentMut, key: &str, root: &Path) -> Option<Vec<String>> {
+    let workspace_members = cargo_toml
         .get("workspace")
-        .and_then(|i| i.get("members"))
-        .and_then(|i| i.as_array())
-    else {
-        return HashMap::new();
-    };
+        .and_then(|i| i.get(key))
+        .and_then(|i| i.as_array())?;
+    let excluded = get_workspace_excluded_paths(cargo_toml);
 
-    workspace_members
+    let mut paths = vec![];
+    for member in workspace_members
         .iter()
-        .fold(HashMap::new(), |mut acc, member| {
-            let Some(member) = member.as_str() else {
-                return acc;
-            };
+        .filter_map(|member| member.as_str())
+    {
+        if !member.contains(['*', '?', '[']) {
+            let member = member.trim_end_matches('/');
+            paths.push(member.strip_prefix("./").unwrap_or(member).to_string());
+            continue;
+        }
+
+        let pattern = Path::new(&glob::Pattern::escape(&root.to_string_lossy())).join(member);
+        let Ok(matches) = glob::glob(&pattern.to_string_lossy()) else {
+            logger::warn(format!("Invalid workspace member pattern `{member}`"));
+            continue;
+        };
 
-            acc.insert(
-                member.to_string(),
-                Box::new(CargoDependencies::gather_dependencies(member)),
-            );
-            acc
+        paths.extend(
+            matches
+                .flatten()
+                .filter(|path| path.join("Cargo.toml").is_file())
+                .map(|path| {
+                    path.strip_prefix(root)
+                        .unwrap_or(&path)
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                })
+                .filter(|path| !is_excluded(path, &excluded)),
+        );
+    }
+
+    paths.sort();
+    paths.dedup();
+    Some(paths)
+}
+
+fn get_workspace_excluded_paths(cargo_toml: &DocumentMut) -> Vec<String> {
+    cargo_toml
+        .get("workspace")
+        .and_then(|i| i.get("exclude"))
+        .and_then(|i| i.as_array())
+        .map(|exclude| {
+            exclude
+                .iter()
+                .filter_map(|path| path.as_str())
+                .map(|path| {
+                    path.trim_start_matches("./")
+                        .trim_end_matches('/')
+                        .to_string()
+                })
+                .collect()
         })
+        .unwrap_or_default()
+}
+
+fn is_excluded(path: &str, excluded: &[String]) -> bool {
+    excluded.iter().any(|excluded| {
+        path == excluded
+            || path.starts_with(&format!("{excluded}/"))
+            || glob::Pattern::new(excluded).is_ok_and(|pattern| pattern.matches(path))
+    })
 }
 
-fn get_package_name(cargo_toml: &DocumentMut) -> String {
+fn get_workspace_dependency_users(
+    workspace_members: &HashMap<String, Box<CargoDependencies>>,
+    name: &str,
+) -> Vec<String> {
+    let mut users = workspace_members
+        .iter()
+        .filter(|(_, member)| {
+            ["dependencies", "dev-dependencies", "build-dependencies"]
+                .iter()
+                .filter_map(|section| member.cargo_toml.get(section)?.get(name))
+                .any(|dependency| {
+                    dependency
+                        .get("workspace")
+                        .and_then(|workspace| workspace.as_bool())
+                        .unwrap_or(false)
+                })
+        })
+        .map(|(path, member)| {
+            member
+                .package_name
+                .clone()
+                .unwrap_or_else(|| path.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    users.sort();
+    users
+}
+
+fn get_package_name(cargo_toml: &DocumentMut) -> Option<String> {
     cargo_toml
         .get("package")
         .and_then(|i| i.get("name"))
         .and_then(|i| i.as_str())
-        .unwrap_or_default()
-        .to_string()
+        .map(|name| name.to_string())
 }
 
 #[cfg(test)]
@@ -247,9 +822,239 @@ mod tests {
         assert_eq!(cargo_dependencies.len(), 2);
     }
 
+    #[test]
+    fn test_cargo_dependencies_filter() {
+        let dependency = |name: &str| CargoDependency {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let mut cargo_dependencies = CargoDependencies {
+            dependencies: vec![dependency("serde"), dependency("tokio"), dependency("log")],
+            workspace_members: HashMap::from_iter([(
+                "".to_string(),
+                Box::new(CargoDependencies {
+                    dependencies: vec![dependency("openssl-sys"), dependency("serde")],
+                    ..Default::default()
+                }),
+            )]),
+            ..Default::default()
+        };
+
+        cargo_dependencies.filter(&[], &["openssl-sys".to_string(), "log".to_string()]);
+        assert_eq!(cargo_dependencies.len(), 3);
+
+        cargo_dependencies.filter(&["serde".to_string()], &[]);
+        assert_eq!(cargo_dependencies.len(), 2);
+        assert_eq!(cargo_dependencies.dependencies[0].name, "serde");
+    }
+
+    #[test]
+    fn test_cargo_dependencies_filter_crate() {
+        let mut cargo_dependencies = CargoDependencies {
+            dependencies: vec![
+                CargoDependency {
+                    name: "serde".to_string(),
+                    ..Default::default()
+                },
+                CargoDependency {
+                    name: "json".to_string(),
+                    package: Some("serde_json".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        cargo_dependencies.filter_crate("serde_json");
+        assert_eq!(cargo_dependencies.len(), 1);
+        assert_eq!(cargo_dependencies.dependencies[0].name, "json");
+    }
+
+    #[test]
+    fn test_cargo_dependencies_filter_packages() {
+        let member = |package_name: &str, cargo_toml: &str| {
+            Box::new(CargoDependencies {
+                cargo_toml: cargo_toml.parse().unwrap(),
+                package_name: Some(package_name.to_string()),
+                dependencies: vec![Default::default()],
+                ..Default::default()
+            })
+        };
+        let mut cargo_dependencies = CargoDependencies {
+            dependencies: vec![
+                Default::default(),
+                CargoDependency {
+                    name: "serde".to_string(),
+                    kind: DependencyKind::Workspace,
+                    ..Default::default()
+                },
+                CargoDependency {
+                    name: "tokio".to_string(),
+                    kind: DependencyKind::Workspace,
+                    ..Default::default()
+                },
+            ],
+            workspace_members: HashMap::from_iter([
+                (
+                    "crates/foo".to_string(),
+                    member("foo", "[dependencies]\nserde = { workspace = true }"),
+                ),
+                (
+                    "crates/bar".to_string(),
+                    member("bar", "[dependencies]\ntokio = { workspace = true }"),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            cargo_dependencies.filter_packages(&["baz".to_string()]),
+            Err(Error::UnknownPackage(package)) if package == "baz"
+        ));
+        assert_eq!(cargo_dependencies.len(), 5);
+
+        assert!(matches!(
+            cargo_dependencies.filter_packages(&["foo".to_string()]),
+            Ok(())
+        ));
+        assert_eq!(cargo_dependencies.len(), 2);
+        assert_eq!(cargo_dependencies.dependencies[0].name, "serde");
+    }
+
+    #[test]
+    fn test_cargo_dependencies_filter_member() {
+        let mut cargo_dependencies = CargoDependencies {
+            dependencies: vec![
+                Default::default(),
+                CargoDependency {
+                    name: "serde".to_string(),
+                    kind: DependencyKind::Workspace,
+                    ..Default::default()
+                },
+            ],
+            workspace_members: HashMap::from_iter([
+                (
+                    "crates/foo".to_string(),
+                    Box::new(CargoDependencies {
+                        cargo_toml: "[dependencies]\nserde = { workspace = true }"
+                            .parse()
+                            .unwrap(),
+                        dependencies: vec![Default::default()],
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    "crates/bar".to_string(),
+                    Box::new(CargoDependencies {
+                        dependencies: vec![Default::default()],
+                        ..Default::default()
+                    }),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        cargo_dependencies.filter_member("crates/foo");
+        assert_eq!(cargo_dependencies.len(), 2);
+        assert_eq!(cargo_dependencies.dependencies[0].name, "serde");
+        assert!(cargo_dependencies
+            .workspace_members
+            .contains_key("crates/foo"));
+    }
+
+    #[test]
+    fn test_cargo_dependencies_filter_default_members() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages/workspace_pointer");
+        let mut cargo_dependencies =
+            CargoDependencies::gather_dependencies(&root.to_string_lossy()).unwrap();
+
+        assert_eq!(
+            cargo_dependencies.default_members,
+            Some(vec!["crates/first".to_string()])
+        );
+        assert_eq!(cargo_dependencies.len(), 2);
+
+        cargo_dependencies.filter_default_members();
+        assert_eq!(cargo_dependencies.len(), 1);
+        assert_eq!(cargo_dependencies.dependencies[0].name, "base64");
+        assert!(cargo_dependencies
+            .workspace_members
+            .contains_key("crates/first"));
+
+        let mut cargo_dependencies = CargoDependencies {
+            dependencies: vec![Default::default()],
+            default_members: Some(vec![".".to_string()]),
+            ..Default::default()
+        };
+        cargo_dependencies.filter_default_members();
+        assert_eq!(cargo_dependencies.len(), 1);
+    }
+
+    #[test]
+    fn test_find_projects() {
+        let packages = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages");
+
+        assert_eq!(
+            find_projects(&packages)
+                .iter()
+                .map(|project| project.strip_prefix(&packages).unwrap().to_string_lossy())
+                .collect::<Vec<_>>(),
+            vec![
+                "glob_members",
+                "glob_members/crates/excluded",
+                "malformed_member",
+                "multiple_packages",
+                "nested_workspace",
+                "nested_workspace/nested",
+                "reexports",
+                "single_package",
+                "workspace_pointer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_workspace_root() {
+        let packages = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages");
+
+        assert_eq!(
+            find_workspace_root(&packages.join("glob_members/crates/first")),
+            Some((packages.join("glob_members"), "crates/first".to_string()))
+        );
+        assert_eq!(
+            find_workspace_root(&packages.join("multiple_packages/first-package")),
+            Some((
+                packages.join("multiple_packages"),
+                "first-package".to_string()
+            ))
+        );
+        assert_eq!(find_workspace_root(&packages.join("glob_members")), None);
+        assert_eq!(
+            find_workspace_root(&packages.join("glob_members/crates/excluded")),
+            None
+        );
+        assert_eq!(
+            find_workspace_root(&packages.join("nested_workspace/nested/inner")),
+            Some((
+                packages.join("nested_workspace/nested"),
+                "inner".to_string()
+            ))
+        );
+        assert_eq!(find_workspace_root(&packages.join("single_package")), None);
+
+        let root = std::fs::canonicalize(packages.join("workspace_pointer")).unwrap();
+        assert_eq!(
+            find_workspace_root(&packages.join("workspace_pointer/crates/second")),
+            Some((root, "crates/second".to_string()))
+        );
+    }
+
     #[test]
     fn test_get_cargo_dependencies() {
         const CARGO_TOML: &str = r#"
+        [package]
+        name = "package"
+
         [dependencies]
         "dependencies" = "0.1.0"
 
@@ -269,25 +1074,54 @@ mod tests {
         assert!(dependencies.contains(&CargoDependency {
             name: "dependencies".to_string(),
             version: "0.1.0".to_string(),
-            kind: DependencyKind::Normal
+            kind: DependencyKind::Normal,
+            package: None,
+            registry: None
         }));
         assert!(dependencies.contains(&CargoDependency {
             name: "dev-dependencies".to_string(),
             version: "1.0.0".to_string(),
-            kind: DependencyKind::Dev
+            kind: DependencyKind::Dev,
+            package: None,
+            registry: None
         }));
         assert!(dependencies.contains(&CargoDependency {
             name: "build-dependencies".to_string(),
             version: "2.0.0".to_string(),
-            kind: DependencyKind::Build
+            kind: DependencyKind::Build,
+            package: None,
+            registry: None
         }));
         assert!(dependencies.contains(&CargoDependency {
             name: "workspace-dependencies".to_string(),
             version: "3.0.0".to_string(),
-            kind: DependencyKind::Workspace
+            kind: DependencyKind::Workspace,
+            package: None,
+            registry: None
         }));
     }
 
+    #[test]
+    fn test_get_cargo_dependencies_with_virtual_workspace() {
+        const CARGO_TOML: &str = r#"
+        [workspace]
+        members = ["first"]
+
+        [workspace.dependencies]
+        "workspace-dependencies" = "3.0.0"
+
+        [dependencies]
+        "dependencies" = "0.1.0"
+        "#;
+
+        let cargo_toml: DocumentMut = CARGO_TOML.parse().unwrap();
+        let dependencies = get_cargo_dependencies(&cargo_toml);
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].name, "workspace-dependencies");
+        assert_eq!(dependencies[0].kind, DependencyKind::Workspace);
+        assert_eq!(get_package_name(&cargo_toml), None);
+    }
+
     #[test]
     fn test_extract_dependencies_from_sections() {
         const CARGO_TOML: &str = r#"
@@ -310,12 +1144,16 @@ mod tests {
         assert!(dependencies.contains(&CargoDependency {
             name: "cargo-outdated".to_string(),
             version: "0.1.0".to_string(),
-            kind: DependencyKind::Normal
+            kind: DependencyKind::Normal,
+            package: None,
+            registry: None
         }));
         assert!(dependencies.contains(&CargoDependency {
             name: "other-dependency".to_string(),
             version: "1.0.0".to_string(),
-            kind: DependencyKind::Normal
+            kind: DependencyKind::Normal,
+            package: None,
+            registry: None
         }));
         // assert!(dependencies.contains(&CargoDependency {
         //     name: "other-name".to_string(),
@@ -325,10 +1163,107 @@ mod tests {
         assert!(dependencies.contains(&CargoDependency {
             name: "serde".to_string(),
             version: "1.0.0".to_string(),
-            kind: DependencyKind::Normal
+            kind: DependencyKind::Normal,
+            package: None,
+            registry: None
         }));
     }
 
+    #[test]
+    fn test_extract_dependencies_with_full_tables_and_renames() {
+        const CARGO_TOML: &str = r#"
+        [workspace.dependencies]
+        serde = { version = "1.0.0", features = ["derive"], default-features = false }
+        json = { package = "serde_json", version = "1.0.100" }
+
+        [workspace.dependencies.toml]
+        package = "toml_edit"
+        version = "0.22.0"
+        features = ["serde"]
+        "#;
+
+        let cargo_toml: DocumentMut = CARGO_TOML.parse().unwrap();
+        let dependencies = extract_dependencies_from_sections(
+            cargo_toml
+                .get("workspace")
+                .and_then(|w| w.get("dependencies")),
+            DependencyKind::Workspace,
+        );
+        assert_eq!(dependencies.len(), 3);
+        assert_eq!(dependencies[0].crate_name(), "serde");
+        assert_eq!(dependencies[1].name, "json");
+        assert_eq!(dependencies[1].crate_name(), "serde_json");
+        assert_eq!(dependencies[2].name, "toml");
+        assert_eq!(dependencies[2].crate_name(), "toml_edit");
+        assert_eq!(dependencies[2].version, "0.22.0");
+    }
+
+    #[test]
+    fn test_extract_dependencies_with_git_sources() {
+        const CARGO_TOML: &str = r#"
+        [dependencies]
+        serde = "1.0.0"
+        tokio = { git = "https://github.com/tokio-rs/tokio", version = "1.2" }
+
+        [dependencies.log]
+        git = "https://github.com/rust-lang/log"
+        branch = "master"
+        version = "0.4"
+        "#;
+
+        let cargo_toml: DocumentMut = CARGO_TOML.parse().unwrap();
+        let dependencies = extract_dependencies_from_sections(
+            cargo_toml.get("dependencies"),
+            DependencyKind::Normal,
+        );
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].name, "serde");
+
+        let warnings = logger::take_warnings();
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.contains("`tokio` has a git source, its version 1.2")));
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.contains("`log` has a git source, its version 0.4")));
+    }
+
+    #[test]
+    fn test_extract_dependencies_with_registry() {
+        const CARGO_TOML: &str = r#"
+        [dependencies]
+        serde = "1.0.0"
+        internal = { version = "1.2.0", registry = "my-registry" }
+        "#;
+
+        let cargo_toml: DocumentMut = CARGO_TOML.parse().unwrap();
+        let dependencies = extract_dependencies_from_sections(
+            cargo_toml.get("dependencies"),
+            DependencyKind::Normal,
+        );
+        assert_eq!(dependencies[0].registry, None);
+        assert_eq!(dependencies[1].name, "internal");
+        assert_eq!(dependencies[1].registry.as_deref(), Some("my-registry"));
+    }
+
+    #[test]
+    fn test_extract_dependencies_with_workspace_inheritance() {
+        const CARGO_TOML: &str = r#"
+        [dependencies]
+        serde = { workspace = true, features = ["derive"] }
+        tokio = { workspace = true, version = "1.0.0" }
+        log = "0.4.0"
+        "#;
+
+        let cargo_toml: DocumentMut = CARGO_TOML.parse().unwrap();
+        let dependencies = extract_dependencies_from_sections(
+            cargo_toml.get("dependencies"),
+            DependencyKind::Normal,
+        );
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].name, "log");
+    }
+
     #[test]
     fn test_extract_dependencies_with_none_dependencies_section() {
         let dependencies = extract_dependencies_from_sections(None, DependencyKind::Normal);
@@ -352,12 +1287,50 @@ mod tests {
         "#;
 
         let cargo_toml = CARGO_TOML.parse().unwrap();
-        let workspace_members = get_workspace_members(&cargo_toml);
+        let workspace_members = get_workspace_members(&cargo_toml, Path::new("."));
         assert_eq!(workspace_members.len(), 2);
         assert!(workspace_members.contains_key("workspace-member-1"));
         assert!(workspace_members.contains_key("workspace-member-2"));
     }
 
+    #[test]
+    fn test_get_workspace_member_paths_with_globs() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages/glob_members");
+        let cargo_toml = read_cargo_file(&root.to_string_lossy()).unwrap();
+
+        assert_eq!(
+            get_workspace_member_paths(&cargo_toml, &root),
+            vec!["crates/first".to_string(), "crates/second".to_string()]
+        );
+
+        let cargo_toml = r#"
+        [workspace]
+        members = ["crates/*", "crates/first/", "other", "missing/*"]
+        exclude = ["./crates/sec*"]
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            get_workspace_member_paths(&cargo_toml, &root),
+            vec![
+                "crates/excluded".to_string(),
+                "crates/first".to_string(),
+                "other".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let excluded = vec!["crates/legacy".to_string(), "examples/*".to_string()];
+
+        assert!(is_excluded("crates/legacy", &excluded));
+        assert!(is_excluded("crates/legacy/nested", &excluded));
+        assert!(is_excluded("examples/demo", &excluded));
+        assert!(!is_excluded("crates/legacy-v2", &excluded));
+        assert!(!is_excluded("crates/foo", &excluded));
+    }
+
     #[test]
     fn test_get_workspace_members_with_no_workspace() {
         const CARGO_TOML: &str = r#"
@@ -366,10 +1339,111 @@ mod tests {
         "#;
 
         let cargo_toml = CARGO_TOML.parse().unwrap();
-        let workspace_members = get_workspace_members(&cargo_toml);
+        let workspace_members = get_workspace_members(&cargo_toml, Path::new("."));
         assert_eq!(workspace_members.len(), 0);
     }
 
+    #[test]
+    fn test_gather_dependencies_with_malformed_member() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages/malformed_member");
+        let cargo_dependencies =
+            CargoDependencies::gather_dependencies(&root.to_string_lossy()).unwrap();
+
+        assert_eq!(
+            cargo_dependencies
+                .workspace_members
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["valid"]
+        );
+        assert_eq!(cargo_dependencies.len(), 2);
+
+        let error =
+            CargoDependencies::gather_dependencies(&root.join("malformed").to_string_lossy())
+                .err()
+                .unwrap();
+        assert!(matches!(error, Error::ManifestParse { .. }));
+        assert!(error.to_string().starts_with("Unable to parse "));
+        assert!(error.to_string().contains("malformed/Cargo.toml"));
+    }
+
+    #[test]
+    fn test_gather_dependencies_with_nested_workspace() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/packages/nested_workspace");
+        let cargo_dependencies =
+            CargoDependencies::gather_dependencies(&root.to_string_lossy()).unwrap();
+
+        assert_eq!(
+            cargo_dependencies.package_name.as_deref(),
+            Some("nested-workspace")
+        );
+        assert_eq!(
+            cargo_dependencies
+                .workspace_members
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["member"]
+        );
+        assert!(cargo_dependencies.workspace_members["member"]
+            .workspace_members
+            .is_empty());
+        assert_eq!(cargo_dependencies.len(), 2);
+    }
+
+    #[test]
+    fn test_get_workspace_dependency_users() {
+        let member = |cargo_toml: &str| {
+            let cargo_toml: DocumentMut = cargo_toml.parse().unwrap();
+            Box::new(CargoDependencies {
+                package_name: get_package_name(&cargo_toml),
+                cargo_toml,
+                ..Default::default()
+            })
+        };
+
+        let workspace_members = HashMap::from_iter([
+            (
+                "first".to_string(),
+                member(
+                    r#"
+                    [package]
+                    name = "first-package"
+
+                    [dependencies]
+                    serde = { workspace = true }
+                    "#,
+                ),
+            ),
+            (
+                "second".to_string(),
+                member(
+                    r#"
+                    [dev-dependencies]
+                    serde = { workspace = true, features = ["derive"] }
+                    "#,
+                ),
+            ),
+            (
+                "third".to_string(),
+                member(
+                    r#"
+                    [package]
+                    name = "third-package"
+
+                    [dependencies]
+                    serde = "1.0.0"
+                    "#,
+                ),
+            ),
+        ]);
+
+        assert_eq!(
+            get_workspace_dependency_users(&workspace_members, "serde"),
+            vec!["first-package".to_string(), "second".to_string()]
+        );
+        assert!(get_workspace_dependency_users(&workspace_members, "tokio").is_empty());
+    }
+
     #[test]
     fn test_get_package_name_with_no_package() {
         const CARGO_TOML: &str = r#"
@@ -379,7 +1453,7 @@ mod tests {
 
         let cargo_toml = CARGO_TOML.parse().unwrap();
         let package_name = get_package_name(&cargo_toml);
-        assert_eq!(package_name, "");
+        assert_eq!(package_name, None);
     }
 
     #[test]
@@ -391,6 +1465,6 @@ mod tests {
 
         let cargo_toml = CARGO_TOML.parse().unwrap();
         let package_name = get_package_name(&cargo_toml);
-        assert_eq!(package_name, "cargo-outdated");
+        assert_eq!(package_name.as_deref(), Some("cargo-outdated"));
     }
 }
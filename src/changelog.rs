@@ -0,0 +1,354 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{mpsc, Arc, Mutex},
+};
+
+use crate::{
+    api,
+    dependency::{Dependencies, Dependency},
+    pool,
+};
+
+pub fn get_github_repository(repository: &str) -> Option<(String, String)> {
+    let path = repository
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .split_once("github.com/")?
+        .1;
+
+    let mut segments = path.split('/');
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    Some((owner.to_string(), repo.to_string()))
+}
+
+// The tag and body of the latest version's release, trying the usual tag naming conventions
+fn find_release(dependency: &Dependency) -> Result<Option<(String, String)>, String> {
+    let Some((owner, repo)) = dependency
+        .repository
+        .as_deref()
+        .and_then(get_github_repository)
+    else {
+        return Ok(None);
+    };
+    let version = &dependency.latest_version;
+
+    for tag in [
+        format!("v{version}"),
+        version.to_string(),
+        format!("{}-v{version}", dependency.crate_name()),
+    ] {
+        if let Some(body) =
+            api::get_github_release_body(&owner, &repo, &tag).map_err(|e| e.to_string())?
+        {
+            return Ok(Some((tag, body)));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn get_release_notes(dependency: &Dependency) -> Option<String> {
+    find_release(dependency)
+        .ok()
+        .flatten()
+        .map(|(_, body)| body)
+}
+
+type ReleaseNotes = Arc<Mutex<HashMap<String, Option<String>>>>;
+
+// Fetches the release notes of the selected dependencies on a background thread while the list
+// is navigated, so showing them doesn't wait on the network. Only done with a GitHub token, the
+// unauthenticated rate limit being too low to spend on notes which may never be shown.
+#[derive(Default)]
+pub struct Prefetcher {
+    enabled: bool,
+    sender: Option<mpsc::Sender<Dependency>>,
+    requested: HashSet<String>,
+    release_notes: ReleaseNotes,
+}
+
+impl Prefetcher {
+    pub fn new() -> Self {
+        Self {
+            enabled: api::has_github_token(),
+            ..Default::default()
+        }
+    }
+
+    pub fn prefetch(&mut self, dependency: &Dependency) {
+        if !self.enabled || !self.requested.insert(get_release_key(dependency)) {
+            return;
+        }
+
+        let sender = self.sender.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel::<Dependency>();
+            let release_notes = Arc::clone(&self.release_notes);
+            std::thread::spawn(move || {
+                // A failed fetch isn't kept, the notes being fetched again once shown
+                for dependency in receiver {
+                    if let Ok(release) = find_release(&dependency) {
+                        release_notes
+                            .lock()
+                            .unwrap()
+                            .insert(get_release_key(&dependency), release.map(|(_, body)| body));
+                    }
+                }
+            });
+            sender
+        });
+        let _ = sender.send(dependency.clone());
+    }
+
+    // Notes still being fetched are fetched again rather than waited on
+    pub fn get(&self, dependency: &Dependency) -> Option<Option<String>> {
+        self.release_notes
+            .lock()
+            .unwrap()
+            .get(&get_release_key(dependency))
+            .cloned()
+    }
+}
+
+// The notes are those of the latest version, which toggling the pre-releases can change
+fn get_release_key(dependency: &Dependency) -> String {
+    format!("{}@{}", dependency.crate_name(), dependency.latest_version)
+}
+
+// The tags follow the naming of the latest version's release when one was found, `v<VERSION>`
+// being the most common otherwise
+fn get_compare_url(dependency: &Dependency, release_tag: Option<&str>) -> Option<String> {
+    let (owner, repo) = get_github_repository(dependency.repository.as_deref()?)?;
+    let new_tag = release_tag
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("v{}", dependency.latest_version));
+    let old_tag = new_tag.replace(&dependency.latest_version, &dependency.current_version);
+
+    Some(format!(
+        "https://github.com/{owner}/{repo}/compare/{old_tag}...{new_tag}"
+    ))
+}
+
+fn render_changelog(dependency: &Dependency, release: Option<(String, String)>) -> Vec<String> {
+    let Some(compare_url) =
+        get_compare_url(dependency, release.as_ref().map(|(tag, _)| tag.as_str()))
+    else {
+        return vec![];
+    };
+
+    let mut lines = vec![
+        format!(
+            "{} {} -> {}",
+            dependency.name, dependency.current_version, dependency.latest_version
+        ),
+        format!("Compare: {compare_url}"),
+    ];
+    if let Some((_, body)) = release {
+        lines.push("Release notes:".to_string());
+        lines.extend(
+            summarize_release_notes(&body, 10)
+                .into_iter()
+                .map(|line| format!("> {line}")),
+        );
+    }
+
+    lines
+}
+
+// Compare links and release note excerpts of the dependencies hosted on GitHub, like the ones
+// Dependabot adds to its pull requests
+pub fn get_changelog(dependencies: &Dependencies) -> String {
+    pool::map(dependencies.iter().collect(), |dependency| {
+        let release = find_release(dependency).ok().flatten();
+        render_changelog(dependency, release)
+    })
+    .into_iter()
+    .filter(|lines| !lines.is_empty())
+    .map(|lines| lines.join("\n"))
+    .collect::<Vec<_>>()
+    .join("\n\n")
+}
+
+pub fn summarize_release_notes(body: &str, max_lines: usize) -> Vec<String> {
+    body.lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.trim().is_empty())
+        .take(max_lines)
+        .map(|line| line.to_string())
+        .collect()
+}
+
+pub fn get_whats_new_lines(dependency: &Dependency, release_notes: Option<&str>) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} {} -> {}",
+        dependency.name, dependency.current_version, dependency.latest_version
+    )];
+
+    let newer_versions = dependency.newer_versions();
+    if let (Some(first), Some(last)) = (newer_versions.first(), newer_versions.last()) {
+        lines.push(format!(
+            "  {} newer version{} released ({} to {})",
+            newer_versions.len(),
+            if newer_versions.len() == 1 { "" } else { "s" },
+            first.num,
+            last.num
+        ));
+    }
+
+    match release_notes {
+        Some(release_notes) => {
+            lines.push("  Release notes:".to_string());
+            lines.extend(
+                summarize_release_notes(release_notes, 10)
+                    .into_iter()
+                    .map(|line| format!("    {line}")),
+            );
+        }
+        None => lines.push(format!(
+            "  No release notes found, see {}",
+            dependency.repository.as_deref().unwrap_or("crates.io")
+        )),
+    }
+
+    lines.push(String::new());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RegistryVersion;
+
+    #[test]
+    fn test_get_github_repository() {
+        assert_eq!(
+            get_github_repository("https://github.com/serde-rs/json"),
+            Some(("serde-rs".to_string(), "json".to_string()))
+        );
+        assert_eq!(
+            get_github_repository("https://github.com/assert-rs/assert_cmd.git"),
+            Some(("assert-rs".to_string(), "assert_cmd".to_string()))
+        );
+        assert_eq!(
+            get_github_repository("https://github.com/tokio-rs/tokio/tree/master/tokio/"),
+            Some(("tokio-rs".to_string(), "tokio".to_string()))
+        );
+        assert_eq!(get_github_repository("https://gitlab.com/user/repo"), None);
+        assert_eq!(get_github_repository("https://github.com/user"), None);
+    }
+
+    #[test]
+    fn test_render_changelog() {
+        let dependency = Dependency {
+            name: "serde".to_string(),
+            current_version: "1.0.1".to_string(),
+            latest_version: "1.0.2".to_string(),
+            repository: Some("https://github.com/serde-rs/serde".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render_changelog(
+                &dependency,
+                Some(("serde-v1.0.2".to_string(), "* Fix a bug\n".to_string()))
+            ),
+            vec![
+                "serde 1.0.1 -> 1.0.2",
+                "Compare: https://github.com/serde-rs/serde/compare/serde-v1.0.1...serde-v1.0.2",
+                "Release notes:",
+                "> * Fix a bug",
+            ]
+        );
+        assert_eq!(
+            render_changelog(&dependency, None)[1],
+            "Compare: https://github.com/serde-rs/serde/compare/v1.0.1...v1.0.2"
+        );
+        assert!(render_changelog(
+            &Dependency {
+                repository: None,
+                ..dependency
+            },
+            None
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_summarize_release_notes() {
+        let body = "## What's Changed\r\n\r\n* Fix a bug\r\n* Add a feature\n\n* Another one";
+        assert_eq!(
+            summarize_release_notes(body, 3),
+            vec!["## What's Changed", "* Fix a bug", "* Add a feature"]
+        );
+    }
+
+    #[test]
+    fn test_prefetcher() {
+        let dependency = Dependency {
+            name: "serde".to_string(),
+            latest_version: "1.0.210".to_string(),
+            ..Default::default()
+        };
+        let mut prefetcher = Prefetcher::default();
+        prefetcher.prefetch(&dependency);
+        assert!(prefetcher.requested.is_empty());
+
+        prefetcher.enabled = true;
+        assert_eq!(prefetcher.get(&dependency), None);
+
+        // Without a repository there is nothing to fetch, the worker stores it right away
+        prefetcher.prefetch(&dependency);
+        prefetcher.prefetch(&dependency);
+        let start = std::time::Instant::now();
+        while prefetcher.get(&dependency).is_none() && start.elapsed().as_secs() < 5 {
+            std::thread::yield_now();
+        }
+        assert_eq!(prefetcher.get(&dependency), Some(None));
+        assert_eq!(prefetcher.requested.len(), 1);
+    }
+
+    #[test]
+    fn test_get_whats_new_lines() {
+        let dependency = Dependency {
+            name: "serde".to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.0.2".to_string(),
+            versions: vec![
+                RegistryVersion {
+                    num: "1.0.2".to_string(),
+                    ..Default::default()
+                },
+                RegistryVersion {
+                    num: "1.0.1".to_string(),
+                    ..Default::default()
+                },
+                RegistryVersion {
+                    num: "1.0.0".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_whats_new_lines(&dependency, Some("* Fix a bug")),
+            vec![
+                "serde 1.0.0 -> 1.0.2",
+                "  2 newer versions released (1.0.1 to 1.0.2)",
+                "  Release notes:",
+                "    * Fix a bug",
+                "",
+            ]
+        );
+        assert_eq!(
+            get_whats_new_lines(&dependency, None)[2],
+            "  No release notes found, see crates.io"
+        );
+    }
+}
@@ -2,22 +2,70 @@ use crossterm::{
     cursor::{Hide, MoveTo, MoveToNextLine, Show},
     event::{self, KeyCode, KeyModifiers},
     execute,
-    style::{Print, PrintStyledContent, ResetColor, Stylize},
-    terminal::{
-        disable_raw_mode, enable_raw_mode, Clear, ClearType, DisableLineWrap, EnableLineWrap,
-    },
+    style::{Print, PrintStyledContent, ResetColor},
+    terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{stdout, Write},
+    time::{Duration, Instant},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    args::Args,
+    changelog,
+    dependency::{self, Dependencies, Dependency, DependencyKind, SortOrder, UpdateMagnitude},
+    frame::{Frame, Screen},
+    policy::Policy,
+    pool, security,
+    theme::Theme,
+    tree,
 };
-use std::io::{stdout, Write};
 
-use crate::dependency::{Dependencies, Dependency, DependencyKind};
+// Longest a held key delays the next frame, so the list still follows the cursor
+const RENDER_INTERVAL: Duration = Duration::from_millis(50);
 
 pub struct State {
     stdout: std::io::Stdout,
     selected: Vec<bool>,
+    default_selected: bool,
+    misaligned: HashSet<String>,
+    hoist: bool,
     cursor_location: usize,
+    dependencies: Dependencies,
     outdated_deps: Dependencies,
+    include_prereleases: bool,
+    section_order: Vec<DependencyKind>,
+    sort: SortOrder,
+    policy: Policy,
+    show_legend: bool,
+    jump_input: Option<String>,
+    active_member: Option<usize>,
     total_deps: usize,
     longest_attributes: Longest,
+    terminal_width: usize,
+    terminal_height: usize,
+    scroll_offset: usize,
+    screen: Screen,
+    last_render: Option<Instant>,
+    release_notes: changelog::Prefetcher,
+    theme: Theme,
+    cargo_flags: Vec<&'static str>,
+    view: View,
+}
+
+enum View {
+    List,
+    Pager {
+        title: String,
+        lines: Vec<String>,
+        offset: usize,
+    },
+    Features {
+        features: Vec<String>,
+        cursor: usize,
+    },
 }
 
 pub enum Event {
@@ -26,15 +74,15 @@ pub enum Event {
     Exit,
 }
 
-struct Longest {
-    name: usize,
-    current_version: usize,
-    latest_version: usize,
-    package_name: usize,
+pub struct Longest {
+    pub name: usize,
+    pub current_version: usize,
+    pub latest_version: usize,
+    pub package_name: usize,
 }
 
 impl Longest {
-    fn get_longest_attributes(dependencies: &Dependencies) -> Longest {
+    pub fn get_longest_attributes(dependencies: &Dependencies) -> Longest {
         let mut name = 0;
         let mut current_version = 0;
         let mut latest_version = 0;
@@ -57,48 +105,135 @@ impl Longest {
 }
 
 impl State {
-    pub fn new(outdated_deps: Dependencies, total_deps: usize, default_selected: bool) -> Self {
+    pub fn new(dependencies: Dependencies, total_deps: usize, args: &Args) -> Self {
+        let section_order = DependencyKind::complete_order(&args.section_order);
+        let policy = Policy::from_args(args);
+        let mut outdated_deps = dependencies.outdated(args.pre_releases);
+        policy.apply(&mut outdated_deps);
+        outdated_deps.sort(&section_order, args.sort);
+        let (terminal_width, terminal_height) = get_terminal_size();
+
         Self {
             stdout: stdout(),
-            selected: vec![default_selected; outdated_deps.len()],
+            selected: outdated_deps
+                .iter()
+                .map(|dep| dep.auto_select.unwrap_or(args.all) && dep.held_back.is_none())
+                .collect(),
+            default_selected: args.all,
+            misaligned: dependencies
+                .get_misaligned_crates()
+                .into_iter()
+                .map(|misaligned| misaligned.name)
+                .collect(),
+            hoist: args.hoist,
             cursor_location: 0,
             longest_attributes: Longest::get_longest_attributes(&outdated_deps),
+            dependencies,
             outdated_deps,
+            include_prereleases: args.pre_releases,
+            section_order,
+            sort: args.sort,
+            policy,
+            show_legend: false,
+            jump_input: None,
+            active_member: None,
             total_deps,
+            terminal_width,
+            terminal_height,
+            scroll_offset: 0,
+            screen: Screen::default(),
+            last_render: None,
+            release_notes: changelog::Prefetcher::new(),
+            theme: args.theme,
+            cargo_flags: args.cargo_flags(),
+            view: View::List,
         }
     }
 
     pub fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         enable_raw_mode()?;
         execute!(self.stdout, Hide)?;
+        self.screen.invalidate();
         Ok(())
     }
 
     pub fn handle_keyboard_event(&mut self) -> Result<Event, Box<dyn std::error::Error>> {
-        if let event::Event::Key(key) = event::read()? {
+        let event = event::read()?;
+
+        if let event::Event::Resize(width, height) = event {
+            self.terminal_width = width as usize;
+            self.terminal_height = height as usize;
+            self.screen.invalidate();
+        }
+
+        if let (event::Event::Key(key), View::Pager { .. }) = (&event, &self.view) {
+            return self.handle_pager_keyboard_event(key.code, key.modifiers);
+        }
+
+        if let (event::Event::Key(key), View::Features { .. }) = (&event, &self.view) {
+            return self.handle_features_keyboard_event(key.code, key.modifiers);
+        }
+
+        if let event::Event::Key(key) = event {
+            if self.handle_jump_input(key.code) {
+                return Ok(Event::HandleKeyboard);
+            }
+
+            if self.outdated_deps.is_empty() && matches!(key.code, KeyCode::Char(' ' | 'p' | 'f')) {
+                return Ok(Event::HandleKeyboard);
+            }
+
             match (key.code, key.modifiers) {
                 (KeyCode::Up | KeyCode::Left, _) => {
-                    self.cursor_location = if self.cursor_location == 0 {
-                        self.outdated_deps.len() - 1
-                    } else {
-                        self.cursor_location - 1
-                    };
+                    self.move_cursor(false);
                 }
                 (KeyCode::Down | KeyCode::Right, _) => {
-                    self.cursor_location = (self.cursor_location + 1) % self.outdated_deps.len();
+                    self.move_cursor(true);
+                }
+                (KeyCode::Tab, _) => {
+                    self.switch_member(true);
+                }
+                (KeyCode::BackTab, _) => {
+                    self.switch_member(false);
+                }
+                (KeyCode::Char('p'), _) => {
+                    let dependency = &mut self.outdated_deps.dependencies[self.cursor_location];
+                    dependency.requirement = dependency.requirement.next();
                 }
                 (KeyCode::Char(' '), _) => {
-                    self.selected[self.cursor_location] = !self.selected[self.cursor_location];
+                    self.set_selected(self.cursor_location, !self.selected[self.cursor_location]);
                 }
                 (KeyCode::Enter, _) => {
                     self.reset_terminal()?;
                     return Ok(Event::UpdateDependencies);
                 }
                 (KeyCode::Char('a'), _) => {
-                    self.selected = vec![true; self.outdated_deps.len()];
+                    for i in self.visible_indices() {
+                        self.set_selected(i, true);
+                    }
                 }
                 (KeyCode::Char('i'), _) => {
-                    self.selected = self.selected.iter().map(|s| !s).collect();
+                    for i in self.visible_indices() {
+                        self.set_selected(i, !self.selected[i]);
+                    }
+                }
+                (KeyCode::Char('r'), _) => {
+                    self.toggle_prereleases();
+                }
+                (KeyCode::Char('?'), _) => {
+                    self.show_legend = !self.show_legend;
+                }
+                (KeyCode::Char('w'), _) => {
+                    self.open_whats_new()?;
+                }
+                (KeyCode::Char('t'), _) => {
+                    self.open_reverse_tree()?;
+                }
+                (KeyCode::Char('f'), _) => {
+                    self.open_feature_editor();
+                }
+                (KeyCode::Char('A'), _) => {
+                    self.align_versions();
                 }
                 (KeyCode::Esc | KeyCode::Char('q'), _)
                 | (KeyCode::Char('c') | KeyCode::Char('z'), KeyModifiers::CONTROL) => {
@@ -112,114 +247,830 @@ impl State {
         Ok(Event::HandleKeyboard)
     }
 
+    fn handle_pager_keyboard_event(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<Event, Box<dyn std::error::Error>> {
+        let page_size = self.pager_page_size();
+        let View::Pager { lines, offset, .. } = &mut self.view else {
+            return Ok(Event::HandleKeyboard);
+        };
+        let max_offset = lines.len().saturating_sub(page_size);
+
+        match (code, modifiers) {
+            (KeyCode::Up, _) => *offset = offset.saturating_sub(1),
+            (KeyCode::Down, _) => *offset = (*offset + 1).min(max_offset),
+            (KeyCode::PageUp, _) => *offset = offset.saturating_sub(page_size),
+            (KeyCode::PageDown, _) => *offset = (*offset + page_size).min(max_offset),
+            (KeyCode::Enter, _) => {
+                self.reset_terminal()?;
+                return Ok(Event::UpdateDependencies);
+            }
+            (KeyCode::Esc | KeyCode::Char('q' | 'w' | 't'), _) => self.view = View::List,
+            (KeyCode::Char('c') | KeyCode::Char('z'), KeyModifiers::CONTROL) => {
+                self.reset_terminal()?;
+                return Ok(Event::Exit);
+            }
+            _ => {}
+        }
+
+        Ok(Event::HandleKeyboard)
+    }
+
+    fn handle_features_keyboard_event(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<Event, Box<dyn std::error::Error>> {
+        let View::Features { features, cursor } = &mut self.view else {
+            return Ok(Event::HandleKeyboard);
+        };
+        let total_features = features.len().max(1);
+
+        match (code, modifiers) {
+            (KeyCode::Up, _) => *cursor = (*cursor + total_features - 1) % total_features,
+            (KeyCode::Down, _) => *cursor = (*cursor + 1) % total_features,
+            (KeyCode::Char(' '), _) => {
+                if let Some(feature) = features.get(*cursor).cloned() {
+                    self.toggle_feature(feature);
+                }
+            }
+            (KeyCode::Enter, _) => {
+                self.reset_terminal()?;
+                return Ok(Event::UpdateDependencies);
+            }
+            (KeyCode::Esc | KeyCode::Char('q' | 'f'), _) => self.view = View::List,
+            (KeyCode::Char('c') | KeyCode::Char('z'), KeyModifiers::CONTROL) => {
+                self.reset_terminal()?;
+                return Ok(Event::Exit);
+            }
+            _ => {}
+        }
+
+        Ok(Event::HandleKeyboard)
+    }
+
+    // Lists the features of the latest version along with the declared ones, which may have been
+    // renamed or removed since
+    fn open_feature_editor(&mut self) {
+        let Some(dependency) = self.outdated_deps.dependencies.get(self.cursor_location) else {
+            return;
+        };
+
+        let mut features = dependency
+            .latest_features()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let enabled = dependency
+            .features
+            .clone()
+            .unwrap_or_else(|| self.outdated_deps.get_declared_features(dependency));
+        for feature in enabled {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+
+        self.view = View::Features {
+            features,
+            cursor: 0,
+        };
+    }
+
+    // The manifest is only rewritten for dependencies whose features have been toggled, which
+    // are selected as well so the change isn't lost
+    fn toggle_feature(&mut self, feature: String) {
+        let i = self.cursor_location;
+        let declared = self
+            .outdated_deps
+            .get_declared_features(&self.outdated_deps.dependencies[i]);
+        let enabled = self.outdated_deps.dependencies[i]
+            .features
+            .get_or_insert(declared);
+
+        match enabled.iter().position(|enabled| *enabled == feature) {
+            Some(position) => {
+                enabled.remove(position);
+            }
+            None => enabled.push(feature),
+        }
+
+        self.set_selected(i, true);
+    }
+
+    fn open_whats_new(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.load_details(self.selected_indices());
+        let selected = self
+            .outdated_deps
+            .iter()
+            .zip(self.selected.iter())
+            .filter(|(_, selected)| **selected)
+            .map(|(dep, _)| dep.clone())
+            .collect::<Vec<_>>();
+
+        execute!(
+            self.stdout,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            Print(format!(
+                "Fetching release notes for {} selected dependencies...",
+                selected.len()
+            ))
+        )?;
+
+        let prefetched = &self.release_notes;
+        let mut lines = pool::map(selected, |dep| {
+            let release_notes = prefetched
+                .get(&dep)
+                .unwrap_or_else(|| changelog::get_release_notes(&dep));
+            changelog::get_whats_new_lines(&dep, release_notes.as_deref())
+        })
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if lines.is_empty() {
+            lines.push("No dependencies are selected.".to_string());
+        }
+
+        self.view = View::Pager {
+            title: "What's new in the selected dependencies:".to_string(),
+            lines,
+            offset: 0,
+        };
+        Ok(())
+    }
+
+    fn open_reverse_tree(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(dependency) = self.outdated_deps.dependencies.get(self.cursor_location) else {
+            return Ok(());
+        };
+        let crate_name = dependency.crate_name().to_string();
+
+        execute!(
+            self.stdout,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            Print(format!("Running cargo tree for {crate_name}..."))
+        )?;
+
+        let lines = tree::get_reverse_tree_lines(&crate_name, &self.cargo_flags)
+            .unwrap_or_else(|error| error.to_string().lines().map(str::to_string).collect());
+
+        self.view = View::Pager {
+            title: format!("Why {crate_name} is locked to its current version:"),
+            lines,
+            offset: 0,
+        };
+        Ok(())
+    }
+
+    fn pager_page_size(&self) -> usize {
+        self.terminal_height.saturating_sub(4).max(1)
+    }
+
+    fn handle_jump_input(&mut self, code: KeyCode) -> bool {
+        match (self.jump_input.is_some(), code) {
+            (_, KeyCode::Char(c)) if c.is_ascii_digit() => {
+                self.jump_input.get_or_insert_with(String::new).push(c);
+            }
+            (false, KeyCode::Char(':')) => self.jump_input = Some(String::new()),
+            (true, KeyCode::Backspace) => {
+                if let Some(jump_input) = self.jump_input.as_mut() {
+                    jump_input.pop();
+                }
+            }
+            (true, KeyCode::Char(' ') | KeyCode::Enter) => self.jump_to_row(),
+            (true, KeyCode::Esc) => self.jump_input = None,
+            (true, _) => {
+                self.jump_input = None;
+                return false;
+            }
+            (false, _) => return false,
+        }
+
+        true
+    }
+
+    fn jump_to_row(&mut self) {
+        let row = self
+            .jump_input
+            .take()
+            .and_then(|jump_input| jump_input.parse::<usize>().ok());
+        let visible = self.visible_indices();
+
+        if let Some(i) = row.and_then(|row| visible.get(row.checked_sub(1)?)) {
+            self.cursor_location = *i;
+            self.set_selected(*i, !self.selected[*i]);
+        }
+    }
+
+    // Selecting every outdated declaration of a misaligned crate updates them all to the same
+    // latest version
+    fn align_versions(&mut self) {
+        let Some(dependency) = self.outdated_deps.dependencies.get(self.cursor_location) else {
+            return;
+        };
+        if !self.is_misaligned(dependency) {
+            return;
+        }
+
+        let crate_name = dependency.crate_name().to_string();
+        for i in 0..self.outdated_deps.dependencies.len() {
+            let dependency = &self.outdated_deps.dependencies[i];
+            if dependency.crate_name() == crate_name && self.is_misaligned(dependency) {
+                self.set_selected(i, true);
+            }
+        }
+    }
+
+    // Dependencies of the same group are selected together
+    fn set_selected(&mut self, i: usize, selected: bool) {
+        let group = self.outdated_deps.dependencies[i].group.clone();
+
+        for (j, dep) in self.outdated_deps.dependencies.iter().enumerate() {
+            if (j == i || (group.is_some() && dep.group == group)) && dep.held_back.is_none() {
+                self.selected[j] = selected;
+            }
+        }
+    }
+
+    fn is_misaligned(&self, dependency: &Dependency) -> bool {
+        dependency.kind != DependencyKind::Workspace
+            && self.misaligned.contains(dependency.crate_name())
+    }
+
+    fn visible_indices(&self) -> Vec<usize> {
+        let members = get_workspace_members(&self.outdated_deps);
+        let active_member = self.active_member.and_then(|i| members.get(i));
+
+        self.outdated_deps
+            .iter()
+            .enumerate()
+            .filter(|(_, dep)| {
+                active_member.is_none_or(|member| member.workspace_path == dep.workspace_path)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let visible = self.visible_indices();
+
+        if visible.is_empty() {
+            return;
+        }
+
+        let position = visible
+            .iter()
+            .position(|i| *i == self.cursor_location)
+            .unwrap_or(0);
+        let position = if forward {
+            (position + 1) % visible.len()
+        } else if position == 0 {
+            visible.len() - 1
+        } else {
+            position - 1
+        };
+
+        self.cursor_location = visible[position];
+    }
+
+    fn switch_member(&mut self, forward: bool) {
+        let total_members = get_workspace_members(&self.outdated_deps).len();
+
+        if total_members <= 1 {
+            return;
+        }
+
+        self.active_member = match (self.active_member, forward) {
+            (None, true) => Some(0),
+            (None, false) => Some(total_members - 1),
+            (Some(i), true) if i + 1 == total_members => None,
+            (Some(i), true) => Some(i + 1),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+        };
+
+        self.reset_cursor_to_visible();
+    }
+
+    fn reset_cursor_to_visible(&mut self) {
+        let visible = self.visible_indices();
+
+        if !visible.contains(&self.cursor_location) {
+            self.cursor_location = visible.first().copied().unwrap_or(0);
+        }
+    }
+
+    fn toggle_prereleases(&mut self) {
+        self.include_prereleases = !self.include_prereleases;
+
+        let previous = self
+            .outdated_deps
+            .iter()
+            .zip(self.selected.iter())
+            .map(|(dep, selected)| {
+                (
+                    dependency_key(dep),
+                    (*selected, dep.requirement, dep.features.clone()),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut outdated_deps = self.dependencies.outdated(self.include_prereleases);
+        self.policy.apply(&mut outdated_deps);
+        outdated_deps.sort(&self.section_order, self.sort);
+        self.selected = outdated_deps
+            .dependencies
+            .iter_mut()
+            .map(|dep| match previous.get(&dependency_key(dep)) {
+                Some((selected, requirement, features)) => {
+                    dep.requirement = *requirement;
+                    dep.features = features.clone();
+                    *selected && dep.held_back.is_none()
+                }
+                None => dep.auto_select.unwrap_or(self.default_selected) && dep.held_back.is_none(),
+            })
+            .collect();
+
+        self.cursor_location = self
+            .cursor_location
+            .min(outdated_deps.len().saturating_sub(1));
+        self.longest_attributes = Longest::get_longest_attributes(&outdated_deps);
+        self.outdated_deps = outdated_deps;
+
+        let total_members = get_workspace_members(&self.outdated_deps).len();
+        if self.active_member.is_some_and(|i| i >= total_members) {
+            self.active_member = None;
+        }
+        self.reset_cursor_to_visible();
+    }
+
     fn reset_terminal(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         execute!(self.stdout, Show, ResetColor)?;
         disable_raw_mode()?;
         Ok(())
     }
 
-    pub fn selected_dependencies(self) -> Dependencies {
-        self.outdated_deps
-            .filter_selected_dependencies(self.selected)
+    fn selected_indices(&self) -> Vec<usize> {
+        (0..self.selected.len())
+            .filter(|i| self.selected[*i])
+            .collect()
+    }
+
+    // Rows read from the index get their crates.io details once shown or selected, the full
+    // list keeping them for when the rows are rebuilt
+    fn load_details(&mut self, rows: Vec<usize>) {
+        let rows = rows
+            .into_iter()
+            .filter(|i| self.outdated_deps.dependencies[*i].details_pending)
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            return;
+        }
+
+        let responses = dependency::get_details(
+            &rows
+                .iter()
+                .map(|i| &self.outdated_deps.dependencies[*i])
+                .collect::<Vec<_>>(),
+        );
+        for (i, response) in rows.into_iter().zip(responses) {
+            let key = dependency_key(&self.outdated_deps.dependencies[i]);
+            if let Some(dependency) = self
+                .dependencies
+                .dependencies
+                .iter_mut()
+                .find(|dependency| dependency_key(dependency) == key)
+            {
+                dependency.set_details(response.clone());
+            }
+            self.outdated_deps.dependencies[i].set_details(response);
+        }
+    }
+
+    // The rows still missing their details have no repository to look the notes up in yet
+    fn prefetch_release_notes(&mut self) {
+        for i in self.selected_indices() {
+            let dependency = &self.outdated_deps.dependencies[i];
+            if !dependency.details_pending {
+                self.release_notes.prefetch(dependency);
+            }
+        }
+    }
+
+    // The cursor is always shown, so the rows within a screen of it cover the viewport
+    fn load_visible_details(&mut self) {
+        let visible = self.visible_indices();
+        let position = visible
+            .iter()
+            .position(|i| *i == self.cursor_location)
+            .unwrap_or(0);
+        let start = position.saturating_sub(self.terminal_height);
+        let end = (position + self.terminal_height).min(visible.len());
+        self.load_details(visible[start..end].to_vec());
+    }
+
+    // Hoisting also rewrites the members which don't declare any selected dependency
+    pub fn selected_dependencies(mut self) -> Dependencies {
+        self.load_details(self.selected_indices());
+        let cargo_toml_files = self.outdated_deps.cargo_toml_files.clone();
+        let mut dependencies = self
+            .outdated_deps
+            .filter_selected_dependencies(self.selected);
+        if self.hoist {
+            dependencies.cargo_toml_files = cargo_toml_files;
+        }
+        dependencies
     }
 
     pub fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.render_header()?;
-        self.render_dependencies()?;
-        self.render_footer_actions()?;
+        self.prefetch_release_notes();
+
+        // Held keys queue events faster than slow terminals draw, the frame is skipped until
+        // they are handled
+        let now = Instant::now();
+        if !is_render_due(self.last_render, now, event::poll(Duration::ZERO)?) {
+            return Ok(());
+        }
+        self.last_render = Some(now);
+
+        if let View::Pager { .. } = self.view {
+            self.screen.invalidate();
+            self.render_pager()?;
+            self.stdout.flush()?;
+            return Ok(());
+        }
+
+        self.load_visible_details();
+        let mut frame = Frame::default();
+        self.render_header(&mut frame);
+        self.render_member_tabs(&mut frame);
+        self.render_dependencies(&mut frame);
+        self.render_highlighted_manifest(&mut frame);
+        self.render_feature_editor(&mut frame);
+        self.render_legend(&mut frame);
+        self.render_footer_actions(&mut frame);
 
+        let lines = frame.into_lines(
+            self.terminal_width,
+            self.terminal_height,
+            &mut self.scroll_offset,
+        );
+        self.screen
+            .draw(&mut self.stdout, lines, self.terminal_width)?;
         self.stdout.flush()?;
         Ok(())
     }
 
-    fn render_header(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    fn render_pager(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let page_size = self.pager_page_size();
+        let View::Pager {
+            title,
+            lines,
+            offset,
+        } = &self.view
+        else {
+            return Ok(());
+        };
+        let page = lines
+            .iter()
+            .skip(*offset)
+            .take(page_size)
+            .map(|line| truncate_with_ellipsis(line, self.terminal_width))
+            .collect::<Vec<_>>();
+
         execute!(
             self.stdout,
             Clear(ClearType::All),
             MoveTo(0, 0),
+            PrintStyledContent(self.theme.accent(title)),
+            MoveToNextLine(2)
+        )?;
+
+        for line in page {
+            execute!(self.stdout, Print(line), MoveToNextLine(1))?;
+        }
+
+        execute!(
+            self.stdout,
+            MoveToNextLine(1),
             Print(format!(
-                "{} out of the {} direct dependencies are outdated.",
-                self.outdated_deps.len().to_string().bold(),
-                self.total_deps.to_string().bold()
-            )),
-            MoveToNextLine(1)
+                "Use {} to scroll, {} to update, {}/{} to go back",
+                self.theme.accent("arrow keys"),
+                self.theme.accent("<enter>"),
+                self.theme.accent("<esc>"),
+                self.theme.accent("<q>")
+            ))
         )?;
         Ok(())
     }
 
-    fn render_dependencies(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut offset = 0;
+    fn render_header(&self, frame: &mut Frame) {
+        frame.print(format!(
+            "{} out of the {} direct dependencies are outdated{}.",
+            self.theme.emphasis(self.outdated_deps.len()),
+            self.theme.emphasis(self.total_deps),
+            if self.include_prereleases {
+                " (including pre-releases)"
+            } else {
+                ""
+            }
+        ));
+        frame.next_line(1);
+        frame.print(get_selection_summary(&self.outdated_deps, &self.selected));
+        frame.next_line(1);
+    }
 
-        execute!(self.stdout, DisableLineWrap)?;
+    fn render_member_tabs(&self, frame: &mut Frame) {
+        let members = get_workspace_members(&self.outdated_deps);
 
-        for kind in DependencyKind::ordered() {
-            offset += self.render_dependencies_subsection(kind, offset)?;
+        if members.len() <= 1 {
+            return;
         }
 
-        execute!(self.stdout, EnableLineWrap)?;
+        let all_tab = " All ".to_string();
+        let mut tabs = vec![if self.active_member.is_none() {
+            self.theme.row(all_tab, true).to_string()
+        } else {
+            all_tab
+        }];
 
-        Ok(())
+        for (i, member) in members.into_iter().enumerate() {
+            let tab = format!(" {} ", member.name);
+            tabs.push(if self.active_member == Some(i) {
+                self.theme.row(tab, true).to_string()
+            } else {
+                tab
+            });
+        }
+
+        frame.next_line(1);
+        frame.print(format!("Members: {}", tabs.join("|")));
+        frame.next_line(1);
+    }
+
+    fn render_dependencies(&self, frame: &mut Frame) {
+        let visible = self.visible_indices();
+
+        frame.start_list();
+        for kind in self.section_order.iter() {
+            self.render_dependencies_subsection(frame, *kind, &visible);
+        }
+        frame.end_list();
     }
 
     fn render_dependencies_subsection(
-        &mut self,
+        &self,
+        frame: &mut Frame,
         kind: DependencyKind,
-        offset: usize,
-    ) -> Result<usize, Box<dyn std::error::Error>> {
-        let deps = self
-            .outdated_deps
+        visible: &[usize],
+    ) {
+        let indices = visible
             .iter()
-            .filter(|dep| dep.kind == kind)
-            .cloned()
+            .copied()
+            .filter(|i| self.outdated_deps.dependencies[*i].kind == kind)
             .collect::<Vec<_>>();
 
-        if deps.is_empty() {
-            return Ok(0);
+        if indices.is_empty() {
+            return;
         }
 
         let title = get_dependencies_subsection_title(kind);
-        let num_selected = self
-            .selected
-            .iter()
-            .zip(self.outdated_deps.iter())
-            .filter(|(selected, dep)| **selected && dep.kind == kind)
-            .count();
+        let num_selected = indices.iter().filter(|i| self.selected[**i]).count();
 
-        execute!(
-            self.stdout,
-            MoveToNextLine(1),
-            PrintStyledContent(format!("{title} ({num_selected} selected):").cyan()),
-            MoveToNextLine(1)
-        )?;
+        frame.next_line(1);
+        frame.print(
+            self.theme
+                .accent(format!("{title} ({num_selected} selected):")),
+        );
+        frame.next_line(1);
+
+        let row_number_width = visible.len().to_string().len();
 
-        for (i, dependency) in deps.iter().enumerate() {
-            self.render_dependency(i + offset, dependency)?;
+        for i in indices {
+            let dependency = &self.outdated_deps.dependencies[i];
+            let row_number = visible.iter().position(|v| *v == i).unwrap_or(0) + 1;
+            let row_number = format!("{row_number:>row_number_width$}");
+            if i == self.cursor_location {
+                frame.mark_cursor();
+            }
+            self.render_dependency(frame, i, &row_number, dependency);
         }
+    }
 
-        Ok(deps.len())
+    fn render_highlighted_manifest(&self, frame: &mut Frame) {
+        let Some(dependency) = self.outdated_deps.dependencies.get(self.cursor_location) else {
+            return;
+        };
+
+        let manifest_path = match &dependency.group {
+            Some(group) => format!("{} (group {group})", dependency.manifest_path()),
+            None => dependency.manifest_path(),
+        };
+        let workspace_users = (dependency.kind == DependencyKind::Workspace).then(|| {
+            format!(
+                "Used by {} member{}: {}",
+                dependency.workspace_users.len(),
+                if dependency.workspace_users.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                dependency.workspace_users.join(", ")
+            )
+        });
+
+        frame.next_line(1);
+        frame.print(format!("Manifest: {}", self.theme.package(manifest_path)));
+
+        if let Some(workspace_users) = workspace_users {
+            frame.next_line(1);
+            frame.print(workspace_users);
+        }
+
+        if let (View::List, Some(features)) = (&self.view, &dependency.features) {
+            frame.next_line(1);
+            frame.print(format!("Features: {}", features.join(", ")));
+        }
     }
 
-    fn render_footer_actions(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        execute!(
-            self.stdout,
-            MoveToNextLine(2),
-            Print(format!(
-                "Use {} to navigate, {} to select all, {} to invert, {} to select/deselect, {} to update, {}/{} to exit",
-                "arrow keys".cyan(),
-                "<a>".cyan(),
-                "<i>".cyan(),
-                "<space>".cyan(),
-                "<enter>".cyan(),
-                "<esc>".cyan(), "<q>".cyan()
-            ))
-        )?;
-        Ok(())
+    fn render_feature_editor(&self, frame: &mut Frame) {
+        let (View::Features { features, cursor }, Some(dependency)) = (
+            &self.view,
+            self.outdated_deps.dependencies.get(self.cursor_location),
+        ) else {
+            return;
+        };
+
+        let enabled = dependency
+            .features
+            .clone()
+            .unwrap_or_else(|| self.outdated_deps.get_declared_features(dependency));
+        let latest_features = dependency.latest_features();
+
+        frame.next_line(2);
+        frame.print(self.theme.accent(format!(
+            "Features of {} {}:",
+            dependency.name, dependency.latest_version
+        )));
+        frame.next_line(1);
+
+        if features.is_empty() {
+            frame.print("  No features available");
+            frame.next_line(1);
+        }
+
+        for (j, feature) in features.iter().enumerate() {
+            let bullet = if enabled.contains(feature) {
+                "●"
+            } else {
+                "○"
+            };
+            let missing = if latest_features.contains(&feature.as_str()) {
+                String::new()
+            } else {
+                format!(" (not in {})", dependency.latest_version)
+            };
+            let line = self
+                .theme
+                .row(format!("  {bullet} {feature}{missing}"), j == *cursor);
+
+            frame.print(line);
+            frame.next_line(1);
+        }
+    }
+
+    fn render_legend(&self, frame: &mut Frame) {
+        if !self.show_legend {
+            return;
+        }
+
+        let api = if self.outdated_deps.iter().any(|dep| dep.reexported) {
+            format!(
+                ", {} breaking update of a re-exported crate",
+                self.theme.warning("API")
+            )
+        } else {
+            String::new()
+        };
+        let unused = if self.outdated_deps.iter().any(|dep| dep.possibly_unused) {
+            format!(
+                ", {} not referenced in the sources",
+                self.theme.warning("unused")
+            )
+        } else {
+            String::new()
+        };
+
+        let misaligned = if self.outdated_deps.iter().any(|dep| self.is_misaligned(dep)) {
+            format!(
+                ", {} declared with other versions by other members",
+                self.theme.warning("misaligned")
+            )
+        } else {
+            String::new()
+        };
+        let advisory = if self
+            .outdated_deps
+            .iter()
+            .any(|dep| !dep.advisories.is_empty())
+        {
+            format!(
+                ", {} severity of the advisories fixed by the update",
+                self.theme.warning("high")
+            )
+        } else {
+            String::new()
+        };
+
+        frame.next_line(2);
+        frame.print(format!(
+                "Legend: ● selected, ○ not selected, {} current version yanked, {} row, {} row, latest version {}, {}, {} update or {}{api}{unused}{misaligned}{advisory}",
+                self.theme.warning("⚠"),
+                self.theme.held_back("held back by policy", false),
+                self.theme.row("highlighted", true),
+                self.theme
+                    .version("major", Some(UpdateMagnitude::Major), false),
+                self.theme
+                    .version("minor", Some(UpdateMagnitude::Minor), false),
+                self.theme
+                    .version("patch", Some(UpdateMagnitude::Patch), false),
+                self.theme.version("pre-release", None, true),
+            ));
+    }
+
+    fn render_footer_actions(&self, frame: &mut Frame) {
+        if let View::Features { .. } = self.view {
+            frame.next_line(1);
+            frame.print(format!(
+                "Use {} to navigate, {} to toggle a feature, {} to update, {}/{} to go back",
+                self.theme.accent("arrow keys"),
+                self.theme.accent("<space>"),
+                self.theme.accent("<enter>"),
+                self.theme.accent("<esc>"),
+                self.theme.accent("<f>")
+            ));
+            return;
+        }
+
+        if let Some(jump_input) = &self.jump_input {
+            frame.next_line(2);
+            frame.print(format!(
+                "Jump to row {}, {}/{} to toggle it, {} to cancel",
+                self.theme.emphasis(format!(":{jump_input}")),
+                self.theme.accent("<space>"),
+                self.theme.accent("<enter>"),
+                self.theme.accent("<esc>")
+            ));
+            return;
+        }
+
+        let mut member_actions = if get_workspace_members(&self.outdated_deps).len() > 1 {
+            format!(
+                "{}/{} to switch member, ",
+                self.theme.accent("<tab>"),
+                self.theme.accent("<shift+tab>")
+            )
+        } else {
+            String::new()
+        };
+        if self.outdated_deps.iter().any(|dep| self.is_misaligned(dep)) {
+            member_actions.push_str(&format!(
+                "{} to align the versions across members, ",
+                self.theme.accent("<A>")
+            ));
+        }
+
+        frame.next_line(2);
+        frame.print(format!(
+                "Use {} to navigate, {} to jump to a row, {member_actions}{} to select all, {} to invert, {} to select/deselect, {} to cycle the requirement, {} to toggle pre-releases, {} to toggle the legend, {} to review what's new, {} to explain the current version, {} to edit the features, {} to update, {}/{} to exit",
+                self.theme.accent("arrow keys"),
+                self.theme.accent("<number>"),
+                self.theme.accent("<a>"),
+                self.theme.accent("<i>"),
+                self.theme.accent("<space>"),
+                self.theme.accent("<p>"),
+                self.theme.accent("<r>"),
+                self.theme.accent("<?>"),
+                self.theme.accent("<w>"),
+                self.theme.accent("<t>"),
+                self.theme.accent("<f>"),
+                self.theme.accent("<enter>"),
+                self.theme.accent("<esc>"),
+                self.theme.accent("<q>")
+            ));
     }
 
     fn render_dependency(
-        &mut self,
+        &self,
+        frame: &mut Frame,
         i: usize,
-        Dependency {
+        row_number: &str,
+        dependency @ Dependency {
             name,
             current_version,
             latest_version,
@@ -228,29 +1079,37 @@ impl State {
             latest_version_date,
             current_version_date,
             package_name,
+            requirement,
             ..
         }: &Dependency,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) {
         let name_spacing = " ".repeat(self.longest_attributes.name - name.len());
         let current_version_spacing =
             " ".repeat(self.longest_attributes.current_version - current_version.len());
+        let magnitude = dependency.magnitude();
+        let prerelease = dependency.is_prerelease_update();
+        let latest_version = requirement.format(latest_version);
         let latest_version_spacing =
-            " ".repeat(self.longest_attributes.latest_version - latest_version.len());
+            " ".repeat(self.longest_attributes.latest_version + 1 - latest_version.len());
 
         let bullet = if self.selected[i] { "●" } else { "○" };
+        let yanked = if dependency.is_current_version_yanked() {
+            "⚠ "
+        } else if self
+            .outdated_deps
+            .iter()
+            .any(|dep| dep.is_current_version_yanked())
+        {
+            "  "
+        } else {
+            ""
+        };
+
+        let latest_version_date =
+            get_date_from_datetime_string(latest_version_date.as_deref()).unwrap_or("          ");
+        let current_version_date =
+            get_date_from_datetime_string(current_version_date.as_deref()).unwrap_or("          ");
 
-        let latest_version_date = get_date_from_datetime_string(latest_version_date.as_deref())
-            .unwrap_or("          ")
-            .italic()
-            .dim();
-        let current_version_date = get_date_from_datetime_string(current_version_date.as_deref())
-            .unwrap_or("          ")
-            .italic()
-            .dim();
-
-        let name = name.clone().bold();
-        let repository = repository.as_deref().unwrap_or("none").underline_black();
-        let description = description.as_deref().unwrap_or("").dim();
         let package_name = if self.outdated_deps.has_workspace_members() {
             let package_name = package_name.as_deref().unwrap_or("");
             let package_name = if package_name.is_empty() {
@@ -262,29 +1121,208 @@ impl State {
             let package_name_spacing =
                 " ".repeat(self.longest_attributes.package_name - package_name.len());
             format!("{package_name}{package_name_spacing}  ")
-                .blue()
-                .italic()
         } else {
-            "".to_string().blue().italic()
+            "".to_string()
         };
 
+        let api = if dependency.reexported && dependency.is_breaking_update() {
+            "API "
+        } else {
+            ""
+        };
+        let unused = if dependency.possibly_unused {
+            "unused "
+        } else {
+            ""
+        };
+        let advisory = security::get_severity(dependency)
+            .map(|severity| format!("{} ", severity.name()))
+            .unwrap_or_default();
+        let misaligned = if self.is_misaligned(dependency) {
+            "misaligned "
+        } else {
+            ""
+        };
+
+        let prefix = format!(
+            "{row_number} {bullet} {yanked}{name}{name_spacing}  {package_name}{current_version_date} {current_version}{current_version_spacing} -> {latest_version_date} {latest_version}{latest_version_spacing}  {advisory}{api}{unused}{misaligned}",
+        );
+        if let Some(reason) = &dependency.held_back {
+            let row = truncate_with_ellipsis(
+                &format!("{prefix}held back: {reason}"),
+                self.terminal_width,
+            );
+            let colored_row = self.theme.held_back(row, i == self.cursor_location);
+
+            frame.print(colored_row);
+            frame.next_line(1);
+            return;
+        }
+
+        // Without room for the repository, the row is cut like a held back one so it doesn't wrap
+        if prefix.width() >= self.terminal_width {
+            let row = truncate_with_ellipsis(&prefix, self.terminal_width);
+            frame.print(self.theme.row(row, i == self.cursor_location));
+            frame.next_line(1);
+            return;
+        }
+
+        let (repository, description) = fit_repository_and_description(
+            repository.as_deref().unwrap_or("none"),
+            description.as_deref().unwrap_or(""),
+            self.terminal_width - prefix.width(),
+        );
+        let separator = if description.is_empty() { "" } else { " - " };
+
+        let yanked = self.theme.warning(yanked);
+        let api = self.theme.warning(api);
+        let unused = self.theme.warning(unused);
+        let advisory = self.theme.warning(advisory);
+        let misaligned = self.theme.warning(misaligned);
+        let name = self.theme.emphasis(name);
+        let package_name = self.theme.package(package_name);
+        let latest_version_date = self.theme.secondary(latest_version_date);
+        let current_version_date = self.theme.secondary(current_version_date);
+        let latest_version = self.theme.version(latest_version, magnitude, prerelease);
+        let repository = self.theme.link(repository);
+        let description = self.theme.description(description);
+
+        let row_number = self.theme.secondary(row_number);
         let row = format!(
-            "{bullet} {name}{name_spacing}  {package_name}{current_version_date} {current_version}{current_version_spacing} -> {latest_version_date} {latest_version}{latest_version_spacing}  {repository} - {description}",
+            "{row_number} {bullet} {yanked}{name}{name_spacing}  {package_name}{current_version_date} {current_version}{current_version_spacing} -> {latest_version_date} {latest_version}{latest_version_spacing}  {advisory}{api}{unused}{misaligned}{repository}{separator}{description}",
         );
 
-        let colored_row = if i == self.cursor_location {
-            row.green()
-        } else {
-            row.black()
+        let colored_row = self.theme.row(row, i == self.cursor_location);
+
+        frame.print(colored_row);
+        frame.next_line(1);
+    }
+}
+
+struct WorkspaceMember {
+    workspace_path: Option<String>,
+    name: String,
+}
+
+fn get_workspace_members(dependencies: &Dependencies) -> Vec<WorkspaceMember> {
+    let mut members = Vec::<WorkspaceMember>::new();
+
+    for dep in dependencies.iter() {
+        if members
+            .iter()
+            .any(|member| member.workspace_path == dep.workspace_path)
+        {
+            continue;
+        }
+
+        let name = match (dep.package_name.as_deref(), dep.workspace_path.as_deref()) {
+            (Some(package_name), _) if !package_name.is_empty() => package_name.to_string(),
+            (_, Some(workspace_path)) => workspace_path.to_string(),
+            (_, None) => "root".to_string(),
         };
 
-        execute!(
-            self.stdout,
-            PrintStyledContent(colored_row),
-            MoveToNextLine(1),
-        )?;
-        Ok(())
+        members.push(WorkspaceMember {
+            workspace_path: dep.workspace_path.clone(),
+            name,
+        });
     }
+
+    members.sort_by(|a, b| {
+        a.workspace_path
+            .is_some()
+            .cmp(&b.workspace_path.is_some())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    members
+}
+
+fn get_selection_summary(dependencies: &Dependencies, selected: &[bool]) -> String {
+    let selected_deps = dependencies
+        .iter()
+        .zip(selected.iter())
+        .filter(|(_, selected)| **selected)
+        .map(|(dep, _)| dep)
+        .collect::<Vec<_>>();
+
+    let count = |magnitude| {
+        selected_deps
+            .iter()
+            .filter(|dep| dep.magnitude() == Some(magnitude))
+            .count()
+    };
+
+    format!(
+        "Selected {}/{} ({} major, {} minor, {} patch)",
+        selected_deps.len(),
+        dependencies.len(),
+        count(UpdateMagnitude::Major),
+        count(UpdateMagnitude::Minor),
+        count(UpdateMagnitude::Patch)
+    )
+}
+
+fn dependency_key(dependency: &Dependency) -> (String, DependencyKind, Option<String>) {
+    (
+        dependency.name.clone(),
+        dependency.kind,
+        dependency.workspace_path.clone(),
+    )
+}
+
+fn is_render_due(last_render: Option<Instant>, now: Instant, pending_input: bool) -> bool {
+    !pending_input
+        || last_render.is_none_or(|last_render| now.duration_since(last_render) >= RENDER_INTERVAL)
+}
+
+fn get_terminal_size() -> (usize, usize) {
+    size().map_or((usize::MAX, usize::MAX), |(width, height)| {
+        (width as usize, height as usize)
+    })
+}
+
+// Widths are the columns taken in the terminal, wide characters such as CJK or emoji taking two
+fn truncate_with_ellipsis(value: &str, max_width: usize) -> String {
+    if value.width() <= max_width {
+        return value.to_string();
+    }
+
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in value.chars() {
+        width += c.width().unwrap_or(0);
+        if width > max_width - 1 {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push('…');
+    truncated
+}
+
+fn fit_repository_and_description(
+    repository: &str,
+    description: &str,
+    available_width: usize,
+) -> (String, String) {
+    let repository_width = repository.width();
+    let separator_width = 3;
+
+    if repository_width + separator_width < available_width && !description.is_empty() {
+        let description_width = available_width - repository_width - separator_width;
+        return (
+            repository.to_string(),
+            truncate_with_ellipsis(description, description_width),
+        );
+    }
+
+    (
+        truncate_with_ellipsis(repository, available_width),
+        String::new(),
+    )
 }
 
 fn get_date_from_datetime_string(datetime_string: Option<&str>) -> Option<&str> {
@@ -299,6 +1337,7 @@ fn get_dependencies_subsection_title(kind: DependencyKind) -> &'static str {
         DependencyKind::Dev => "Dev dependencies",
         DependencyKind::Build => "Build dependencies",
         DependencyKind::Workspace => "Workspace dependencies",
+        DependencyKind::Toolchain => "Toolchain",
     }
 }
 
@@ -333,6 +1372,18 @@ mod tests {
         assert_eq!(longest.package_name, 12);
     }
 
+    #[test]
+    fn test_is_render_due() {
+        let now = Instant::now();
+        let recent = Some(now);
+        let stale = now.checked_sub(RENDER_INTERVAL);
+
+        assert!(is_render_due(recent, now, false));
+        assert!(!is_render_due(recent, now, true));
+        assert!(is_render_due(stale, now, true));
+        assert!(is_render_due(None, now, true));
+    }
+
     #[test]
     fn test_get_date_from_datetime_string() {
         assert_eq!(
@@ -365,4 +1416,209 @@ mod tests {
             "Workspace dependencies"
         );
     }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("description", 20), "description");
+        assert_eq!(truncate_with_ellipsis("description", 11), "description");
+        assert_eq!(truncate_with_ellipsis("description", 5), "desc…");
+        assert_eq!(truncate_with_ellipsis("description", 1), "…");
+        assert_eq!(truncate_with_ellipsis("description", 0), "");
+        assert_eq!(truncate_with_ellipsis("日本語の説明", 12), "日本語の説明");
+        assert_eq!(truncate_with_ellipsis("日本語の説明", 6), "日本…");
+        assert_eq!(truncate_with_ellipsis("日本語の説明", 4), "日…");
+        assert_eq!(truncate_with_ellipsis("🦀 crab", 3), "🦀…");
+    }
+
+    #[test]
+    fn test_fit_repository_and_description() {
+        assert_eq!(
+            fit_repository_and_description("https://repo", "A description", 100),
+            ("https://repo".to_string(), "A description".to_string())
+        );
+        assert_eq!(
+            fit_repository_and_description("https://repo", "A description", 20),
+            ("https://repo".to_string(), "A de…".to_string())
+        );
+        assert_eq!(
+            fit_repository_and_description("https://repo", "A description", 12),
+            ("https://repo".to_string(), "".to_string())
+        );
+        assert_eq!(
+            fit_repository_and_description("https://repo", "A description", 8),
+            ("https:/…".to_string(), "".to_string())
+        );
+        assert_eq!(
+            fit_repository_and_description("https://repo", "", 100),
+            ("https://repo".to_string(), "".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_workspace_members() {
+        let dependencies = Dependencies::new(
+            vec![
+                Dependency {
+                    package_name: Some("second".to_string()),
+                    workspace_path: Some("crates/second".to_string()),
+                    ..Default::default()
+                },
+                Dependency {
+                    package_name: Some("".to_string()),
+                    ..Default::default()
+                },
+                Dependency {
+                    package_name: Some("first".to_string()),
+                    workspace_path: Some("crates/first".to_string()),
+                    ..Default::default()
+                },
+                Dependency {
+                    package_name: Some("".to_string()),
+                    workspace_path: Some("crates/first".to_string()),
+                    ..Default::default()
+                },
+            ],
+            HashMap::new(),
+        );
+
+        let members = get_workspace_members(&dependencies);
+        assert_eq!(
+            members
+                .iter()
+                .map(|member| member.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["root", "first", "second"]
+        );
+        assert_eq!(members[1].workspace_path, Some("crates/first".to_string()));
+    }
+
+    fn state(dependencies: Vec<Dependency>) -> State {
+        let crate::args::CargoCli::InteractiveUpdate(args) =
+            clap::Parser::parse_from(["cargo", "interactive-update"]);
+        State::new(Dependencies::new(dependencies, HashMap::new()), 0, &args)
+    }
+
+    fn outdated_dependency(name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "2.0.0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_selection_summary() {
+        let dependency = |latest_version: &str| Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: latest_version.to_string(),
+            ..Default::default()
+        };
+        let dependencies = Dependencies::new(
+            vec![
+                dependency("2.0.0"),
+                dependency("1.1.0"),
+                dependency("1.2.0"),
+                dependency("1.0.1"),
+            ],
+            HashMap::new(),
+        );
+
+        assert_eq!(
+            get_selection_summary(&dependencies, &[true, true, false, true]),
+            "Selected 3/4 (1 major, 1 minor, 1 patch)"
+        );
+        assert_eq!(
+            get_selection_summary(&dependencies, &[false; 4]),
+            "Selected 0/4 (0 major, 0 minor, 0 patch)"
+        );
+    }
+
+    #[test]
+    fn test_jump_to_row() {
+        let mut state = state(vec![
+            outdated_dependency("a"),
+            outdated_dependency("b"),
+            outdated_dependency("c"),
+        ]);
+
+        assert!(state.handle_jump_input(KeyCode::Char('2')));
+        assert!(state.handle_jump_input(KeyCode::Char(' ')));
+        assert_eq!(state.cursor_location, 1);
+        assert_eq!(state.selected, vec![false, true, false]);
+        assert_eq!(state.jump_input, None);
+
+        assert!(state.handle_jump_input(KeyCode::Char(':')));
+        assert!(state.handle_jump_input(KeyCode::Char('3')));
+        assert!(state.handle_jump_input(KeyCode::Enter));
+        assert_eq!(state.cursor_location, 2);
+        assert_eq!(state.selected, vec![false, true, true]);
+
+        assert!(state.handle_jump_input(KeyCode::Char('9')));
+        assert!(state.handle_jump_input(KeyCode::Enter));
+        assert_eq!(state.cursor_location, 2);
+        assert_eq!(state.selected, vec![false, true, true]);
+
+        assert!(state.handle_jump_input(KeyCode::Char('1')));
+        assert!(state.handle_jump_input(KeyCode::Esc));
+        assert_eq!(state.jump_input, None);
+        assert!(!state.handle_jump_input(KeyCode::Char('a')));
+    }
+
+    #[test]
+    fn test_feature_editor() {
+        let mut dependency = outdated_dependency("tokio");
+        dependency.versions = vec![crate::api::RegistryVersion {
+            num: "2.0.0".to_string(),
+            features: vec!["default".to_string(), "full".to_string(), "rt".to_string()],
+            ..Default::default()
+        }];
+        let mut state = state(vec![dependency]);
+        state.outdated_deps.cargo_toml_files.insert(
+            ".".to_string(),
+            std::sync::Arc::new(
+                "[dependencies]\ntokio = { version = \"1.0.0\", features = [\"macros\"] }\n"
+                    .parse()
+                    .unwrap(),
+            ),
+        );
+
+        state.open_feature_editor();
+        let View::Features { features, .. } = &state.view else {
+            panic!("the feature editor should be open");
+        };
+        assert_eq!(features, &["full", "rt", "macros"]);
+        assert_eq!(state.outdated_deps.dependencies[0].features, None);
+
+        state.toggle_feature("full".to_string());
+        state.toggle_feature("macros".to_string());
+        assert_eq!(
+            state.outdated_deps.dependencies[0].features,
+            Some(vec!["full".to_string()])
+        );
+        assert!(state.selected[0]);
+    }
+
+    #[test]
+    fn test_align_versions() {
+        let dependency = |name: &str, current_version: &str, workspace_path: &str| Dependency {
+            current_version: current_version.to_string(),
+            workspace_path: Some(workspace_path.to_string()),
+            ..outdated_dependency(name)
+        };
+        let mut state = state(vec![
+            dependency("serde", "1.0.0", "a"),
+            dependency("serde", "1.1.0", "b"),
+            dependency("tokio", "1.0.0", "a"),
+        ]);
+        assert_eq!(state.misaligned, HashSet::from(["serde".to_string()]));
+
+        state.cursor_location = 2;
+        state.align_versions();
+        assert_eq!(state.selected, vec![false, false, false]);
+
+        state.cursor_location = 1;
+        state.align_versions();
+        assert_eq!(state.selected, vec![true, true, false]);
+    }
 }
@@ -0,0 +1,584 @@
+use clap::{parser::ValueSource, ArgMatches, Command, CommandFactory, Parser};
+use std::{collections::BTreeMap, ffi::OsString, path::PathBuf};
+use toml_edit::{DocumentMut, Value};
+
+use crate::{
+    args::{Args, CargoCli},
+    hooks::Hooks,
+    rules::Rule,
+};
+
+const SUBCOMMAND: &str = "interactive-update";
+const PROJECT_CONFIG_FILE: &str = ".cargo-interactive-update.toml";
+const ENV_PREFIX: &str = "CARGO_INTERACTIVE_UPDATE_";
+// Built-in profiles, which configuration files can extend or override with `[profiles.<name>]`
+const PROFILES: [(&str, &str); 3] = [
+    (
+        "safe",
+        "compatible-only = true\nmin-age = 14\nrespect-rust-version = true",
+    ),
+    ("default", ""),
+    ("aggressive", "all = true\npre-releases = true"),
+];
+// Cargo configuration keys and environment variables used as defaults for our own arguments
+const CARGO_CONFIG_KEYS: [(&str, &str, &str, &str); 2] = [
+    ("term", "color", "CARGO_TERM_COLOR", "color"),
+    ("net", "offline", "CARGO_NET_OFFLINE", "offline"),
+];
+
+pub fn parse_args() -> Result<Args, Box<dyn std::error::Error>> {
+    let cli_args = std::env::args_os().collect::<Vec<_>>();
+    let command = CargoCli::command();
+    let matches = command.clone().get_matches_from(&cli_args);
+
+    let (Some(subcommand), Some(sub_matches)) = (
+        command.find_subcommand(SUBCOMMAND),
+        matches.subcommand_matches(SUBCOMMAND),
+    ) else {
+        let CargoCli::InteractiveUpdate(args) = CargoCli::parse_from(cli_args);
+        return Ok(args);
+    };
+
+    let mut cargo_config_files = vec![];
+    for path in get_cargo_config_paths() {
+        if let Some(document) = read_config_file(&path)? {
+            cargo_config_files.push(document);
+        }
+    }
+
+    let mut config_files = vec![(
+        PathBuf::from("cargo configuration"),
+        get_cargo_config(&cargo_config_files, std::env::vars()),
+    )];
+    for path in [
+        get_user_config_path(),
+        Some(PathBuf::from(PROJECT_CONFIG_FILE)),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Some(document) = read_config_file(&path)? {
+            config_files.push((path, document));
+        }
+    }
+
+    let env_vars = get_env_vars(std::env::vars());
+    let config_args = get_config_args(subcommand, sub_matches, &config_files, &env_vars)?;
+
+    let CargoCli::InteractiveUpdate(mut args) = CargoCli::parse_from(
+        cli_args
+            .iter()
+            .take(2)
+            .cloned()
+            .chain(config_args.into_iter().map(OsString::from))
+            .chain(cli_args.iter().skip(2).cloned()),
+    );
+    args.rules = get_rules(&config_files)?;
+    args.hooks = get_hooks(&config_files)?;
+
+    Ok(args)
+}
+
+fn get_user_config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+
+    Some(
+        config_dir
+            .join("cargo-interactive-update")
+            .join("config.toml"),
+    )
+}
+
+// Ordered by increasing precedence, with the directories closer to the current one taking
+// precedence like cargo does
+fn get_cargo_config_paths() -> Vec<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".cargo"))
+        });
+    let current_dir = std::env::current_dir().ok();
+    let mut directories = current_dir
+        .iter()
+        .flat_map(|dir| dir.ancestors())
+        .map(|dir| dir.join(".cargo"))
+        .collect::<Vec<_>>();
+    if let Some(cargo_home) = cargo_home.filter(|dir| !directories.contains(dir)) {
+        directories.push(cargo_home);
+    }
+
+    directories
+        .into_iter()
+        .rev()
+        .filter_map(|dir| {
+            ["config.toml", "config"]
+                .into_iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+fn get_cargo_config(
+    cargo_config_files: &[DocumentMut],
+    vars: impl Iterator<Item = (String, String)>,
+) -> DocumentMut {
+    let vars = vars.collect::<BTreeMap<_, _>>();
+    let mut document = DocumentMut::new();
+
+    for (table, key, env_var, long) in CARGO_CONFIG_KEYS {
+        let from_files = cargo_config_files
+            .iter()
+            .rev()
+            .find_map(|config| config.get(table)?.get(key)?.as_value().cloned());
+        let from_env = vars.get(env_var).map(|value| match value.as_str() {
+            "true" => Value::from(true),
+            "false" => Value::from(false),
+            value => Value::from(value),
+        });
+
+        if let Some(value) = from_env.or(from_files) {
+            document.insert(long, toml_edit::Item::Value(value));
+        }
+    }
+
+    document
+}
+
+fn read_config_file(path: &PathBuf) -> Result<Option<DocumentMut>, String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+
+    content
+        .parse()
+        .map(Some)
+        .map_err(|e| format!("Unable to parse {}: {e}", path.display()))
+}
+
+fn get_env_vars(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut env_vars = vars
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect::<Vec<_>>();
+    env_vars.sort();
+    env_vars
+}
+
+// Values of the options by long name, along with where they are set
+type Options = BTreeMap<String, (Value, String)>;
+
+// Files are ordered by increasing precedence, followed by the environment variables, and options
+// given on the command line are skipped
+fn get_config_args(
+    command: &Command,
+    matches: &ArgMatches,
+    config_files: &[(PathBuf, DocumentMut)],
+    env_vars: &[(String, String)],
+) -> Result<Vec<String>, String> {
+    let find_arg = |long: &str| {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long))
+    };
+
+    let mut options = Options::new();
+    for (path, document) in config_files {
+        let source = path.display().to_string();
+        for (key, item) in document.iter() {
+            if key == "profiles" || key == "rules" || key == "hooks" {
+                continue;
+            }
+
+            let value = item
+                .as_value()
+                .ok_or_else(|| format!("Unsupported value for `{key}` in {source}"))?;
+            options.insert(key.replace('_', "-"), (value.clone(), source.clone()));
+        }
+    }
+
+    // A profile takes precedence over the configuration files, but not over the environment
+    let profile = matches
+        .get_one::<String>("profile")
+        .cloned()
+        .or_else(|| {
+            env_vars
+                .iter()
+                .find(|(name, _)| name[ENV_PREFIX.len()..] == *"PROFILE")
+                .map(|(_, value)| value.to_string())
+        })
+        .or_else(|| {
+            options
+                .get("profile")
+                .and_then(|(value, _)| value.as_str())
+                .map(str::to_string)
+        });
+    if let Some(profile) = profile {
+        options.extend(get_profile_options(&profile, config_files)?);
+    }
+
+    // The prefix is shared with the variables given to `--post-cmd`, so unknown names are ignored
+    for (name, raw_value) in env_vars {
+        let long = name[ENV_PREFIX.len()..].to_lowercase().replace('_', "-");
+        let Some(arg) = find_arg(&long) else {
+            continue;
+        };
+
+        let value = if arg.get_action().takes_values() {
+            Value::from(raw_value.as_str())
+        } else {
+            Value::from(matches!(raw_value.as_str(), "1" | "true" | "yes"))
+        };
+        options.insert(long, (value, name.to_string()));
+    }
+
+    let mut args = vec![];
+    for (long, (value, source)) in options {
+        let arg = find_arg(&long).ok_or_else(|| format!("Unknown option `{long}` in {source}"))?;
+
+        if matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine) {
+            continue;
+        }
+
+        args.extend(
+            get_value_args(&long, &value)
+                .ok_or_else(|| format!("Unsupported value for `{long}` in {source}"))?,
+        );
+    }
+
+    Ok(args)
+}
+
+fn get_profile_options(
+    profile: &str,
+    config_files: &[(PathBuf, DocumentMut)],
+) -> Result<Options, String> {
+    let mut found = false;
+    let mut options = Options::new();
+
+    if let Some((_, content)) = PROFILES.iter().find(|(name, _)| *name == profile) {
+        found = true;
+        let document = content.parse::<DocumentMut>().unwrap_or_default();
+        for (key, item) in document.iter() {
+            if let Some(value) = item.as_value() {
+                options.insert(
+                    key.to_string(),
+                    (value.clone(), format!("profile `{profile}`")),
+                );
+            }
+        }
+    }
+
+    for (path, document) in config_files {
+        let Some(table) = document
+            .get("profiles")
+            .and_then(|profiles| profiles.get(profile))
+            .and_then(|table| table.as_table_like())
+        else {
+            continue;
+        };
+
+        found = true;
+        let source = format!("[profiles.{profile}] of {}", path.display());
+        for (key, item) in table.iter() {
+            let value = item
+                .as_value()
+                .ok_or_else(|| format!("Unsupported value for `{key}` in {source}"))?;
+            options.insert(key.replace('_', "-"), (value.clone(), source.clone()));
+        }
+    }
+
+    if !found {
+        return Err(format!("Unknown profile `{profile}`"));
+    }
+
+    Ok(options)
+}
+
+// Rules of every file apply in order, so the project's rules override the user's ones
+fn get_rules(config_files: &[(PathBuf, DocumentMut)]) -> Result<Vec<Rule>, String> {
+    let mut rules = vec![];
+
+    for (path, document) in config_files {
+        let source = path.display().to_string();
+        match document.get("rules") {
+            None => {}
+            Some(toml_edit::Item::ArrayOfTables(tables)) => {
+                for table in tables.iter() {
+                    rules.push(Rule::from_item(
+                        &toml_edit::Item::Table(table.clone()),
+                        &source,
+                    )?);
+                }
+            }
+            Some(toml_edit::Item::Value(Value::Array(values))) => {
+                for value in values.iter() {
+                    rules.push(Rule::from_item(
+                        &toml_edit::Item::Value(value.clone()),
+                        &source,
+                    )?);
+                }
+            }
+            Some(_) => return Err(format!("Rules in {source} must be tables")),
+        }
+    }
+
+    Ok(rules)
+}
+
+// Hooks of every file run in order, the user's ones before the project's ones
+fn get_hooks(config_files: &[(PathBuf, DocumentMut)]) -> Result<Hooks, String> {
+    let mut hooks = Hooks::default();
+
+    for (path, document) in config_files {
+        if let Some(item) = document.get("hooks") {
+            hooks.extend_from_item(item, &path.display().to_string())?;
+        }
+    }
+
+    Ok(hooks)
+}
+
+fn get_value_args(long: &str, value: &Value) -> Option<Vec<String>> {
+    Some(match value {
+        Value::Boolean(enabled) if *enabled.value() => vec![format!("--{long}")],
+        Value::Boolean(_) => vec![],
+        Value::String(value) => vec![format!("--{long}={}", value.value())],
+        Value::Integer(value) => vec![format!("--{long}={}", value.value())],
+        Value::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                Value::String(value) => Some(format!("--{long}={}", value.value())),
+                Value::Integer(value) => Some(format!("--{long}={}", value.value())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_args(cli_args: &[&str], config_files: &[(&str, &str)]) -> Result<Vec<String>, String> {
+        get_args_with_env(cli_args, config_files, &[])
+    }
+
+    fn get_args_with_env(
+        cli_args: &[&str],
+        config_files: &[(&str, &str)],
+        env_vars: &[(&str, &str)],
+    ) -> Result<Vec<String>, String> {
+        let command = CargoCli::command();
+        let matches = command
+            .clone()
+            .get_matches_from(["cargo", SUBCOMMAND].iter().chain(cli_args.iter()));
+        let config_files = config_files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.parse().unwrap()))
+            .collect::<Vec<_>>();
+
+        get_config_args(
+            command.find_subcommand(SUBCOMMAND).unwrap(),
+            matches.subcommand_matches(SUBCOMMAND).unwrap(),
+            &config_files,
+            &get_env_vars(
+                env_vars
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string())),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_get_config_args() {
+        let user = r#"
+        theme = "high-contrast"
+        pin = "tilde"
+        ignore = ["openssl-sys", "rand"]
+        all = true
+        "#;
+        let project = r#"
+        pin = "exact"
+        all = false
+        min_age = 7
+        "#;
+
+        assert_eq!(
+            get_args(&[], &[("user.toml", user), ("project.toml", project)]),
+            Ok(vec![
+                "--ignore=openssl-sys".to_string(),
+                "--ignore=rand".to_string(),
+                "--min-age=7".to_string(),
+                "--pin=exact".to_string(),
+                "--theme=high-contrast".to_string(),
+            ])
+        );
+        assert_eq!(
+            get_args(
+                &["--theme", "default", "--min-age=1"],
+                &[("user.toml", user), ("project.toml", project)]
+            ),
+            Ok(vec![
+                "--ignore=openssl-sys".to_string(),
+                "--ignore=rand".to_string(),
+                "--pin=exact".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_config_args_with_env() {
+        assert_eq!(
+            get_args_with_env(
+                &["--min-age", "3"],
+                &[("project.toml", "theme = \"high-contrast\"\nall = true")],
+                &[
+                    ("CARGO_INTERACTIVE_UPDATE_THEME", "default"),
+                    ("CARGO_INTERACTIVE_UPDATE_ALL", "false"),
+                    ("CARGO_INTERACTIVE_UPDATE_NO_CHECK", "1"),
+                    ("CARGO_INTERACTIVE_UPDATE_MIN_AGE", "7"),
+                    ("CARGO_INTERACTIVE_UPDATE_IGNORE", "rand,openssl-sys"),
+                    ("CARGO_INTERACTIVE_UPDATE_COUNT", "2"),
+                    ("PATH", "/usr/bin"),
+                ]
+            ),
+            Ok(vec![
+                "--ignore=rand,openssl-sys".to_string(),
+                "--no-check".to_string(),
+                "--theme=default".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_config_args_profile() {
+        let user = r#"
+        min-age = 3
+        verify = "test"
+
+        [profiles.quarterly]
+        all = true
+        ignore = ["openssl-sys"]
+        "#;
+        let project = r#"
+        profile = "safe"
+
+        [profiles.safe]
+        min-age = 30
+        "#;
+
+        assert_eq!(
+            get_args(&[], &[("user.toml", user), ("project.toml", project)]),
+            Ok(vec![
+                "--compatible-only".to_string(),
+                "--min-age=30".to_string(),
+                "--profile=safe".to_string(),
+                "--respect-rust-version".to_string(),
+                "--verify=test".to_string(),
+            ])
+        );
+        assert_eq!(
+            get_args_with_env(
+                &["--profile", "quarterly"],
+                &[("user.toml", user)],
+                &[("CARGO_INTERACTIVE_UPDATE_ALL", "false")]
+            ),
+            Ok(vec![
+                "--ignore=openssl-sys".to_string(),
+                "--min-age=3".to_string(),
+                "--verify=test".to_string(),
+            ])
+        );
+        assert_eq!(
+            get_args(&["--profile", "aggressive"], &[]),
+            Ok(vec!["--all".to_string(), "--pre-releases".to_string()])
+        );
+        assert_eq!(
+            get_args(&["--profile", "unknown"], &[("user.toml", user)]),
+            Err("Unknown profile `unknown`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_config_args_errors() {
+        assert_eq!(
+            get_args(&[], &[("project.toml", "unknown = true")]),
+            Err("Unknown option `unknown` in project.toml".to_string())
+        );
+        assert_eq!(
+            get_args(&[], &[("project.toml", "[theme]")]),
+            Err("Unsupported value for `theme` in project.toml".to_string())
+        );
+        assert_eq!(
+            get_args(&[], &[("project.toml", "ignore = [true]")]),
+            Err("Unsupported value for `ignore` in project.toml".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_cargo_config() {
+        let home: DocumentMut = "[term]\ncolor = \"always\"\n[net]\noffline = true"
+            .parse()
+            .unwrap();
+        let project: DocumentMut = "[term]\ncolor = \"never\"\nverbose = true".parse().unwrap();
+
+        let cargo_config = get_cargo_config(&[home.clone(), project], std::iter::empty());
+        assert_eq!(
+            cargo_config.to_string(),
+            "color = \"never\"\noffline = true\n"
+        );
+
+        let cargo_config = get_cargo_config(
+            &[home],
+            [
+                ("CARGO_TERM_COLOR".to_string(), "auto".to_string()),
+                ("CARGO_NET_OFFLINE".to_string(), "false".to_string()),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            cargo_config.to_string(),
+            "color = \"auto\"\noffline = false\n"
+        );
+
+        let cargo_config = (PathBuf::from("cargo configuration"), cargo_config);
+        let user = (
+            PathBuf::from("user.toml"),
+            "offline = true".parse().unwrap(),
+        );
+        let command = CargoCli::command();
+        let matches = command
+            .clone()
+            .get_matches_from(["cargo", SUBCOMMAND, "--color", "always"]);
+        assert_eq!(
+            get_config_args(
+                command.find_subcommand(SUBCOMMAND).unwrap(),
+                matches.subcommand_matches(SUBCOMMAND).unwrap(),
+                &[cargo_config, user],
+                &[],
+            ),
+            Ok(vec!["--offline".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_config_args_parse() {
+        let CargoCli::InteractiveUpdate(args) = CargoCli::parse_from(
+            ["cargo", SUBCOMMAND]
+                .into_iter()
+                .map(|arg| arg.to_string())
+                .chain(get_args(&[], &[("user.toml", r#"verify = "test""#)]).unwrap()),
+        );
+
+        assert_eq!(args.verify, crate::dependency::VerifyCommand::Test);
+    }
+}
@@ -1,12 +1,30 @@
-use crossterm::style::Stylize;
-use std::collections::{HashMap, HashSet};
+use semver::{Version, VersionReq};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::IsTerminal,
+    sync::Arc,
+};
 use toml_edit::{value, DocumentMut, Item, Value};
 
-use crate::args::Args;
+use crate::{
+    api::{Advisory, CratesIoResponse, RegistryVersion},
+    args::Args,
+    cargo::{self, CargoDependency},
+    changelog, diff,
+    error::Error,
+    git, history, hoist,
+    hooks::{self, Hook},
+    logger, security, semver_checks,
+    theme::Theme,
+    toolchain,
+};
 
+/// A dependency declared in a manifest, along with its versions published on crates.io
 #[derive(Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub struct Dependency {
     pub name: String,
+    pub package: Option<String>,
     pub current_version: String,
     pub latest_version: String,
     pub repository: Option<String>,
@@ -16,6 +34,177 @@ pub struct Dependency {
     pub kind: DependencyKind,
     pub package_name: Option<String>,
     pub workspace_path: Option<String>,
+    pub requirement: Requirement,
+    pub versions: Vec<RegistryVersion>,
+    pub workspace_users: Vec<String>,
+    pub held_back: Option<String>,
+    pub reexported: bool,
+    pub possibly_unused: bool,
+    pub group: Option<String>,
+    pub auto_select: Option<bool>,
+    pub features: Option<Vec<String>>,
+    pub advisories: Vec<Advisory>,
+    pub details_pending: bool,
+    pub unknown_source: Option<String>,
+}
+
+impl Dependency {
+    pub fn crate_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    fn latest_registry_version(&self, include_prereleases: bool) -> Option<&RegistryVersion> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| Some((Version::parse(&v.num).ok()?, v)))
+            .filter(|(version, _)| include_prereleases || version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v)
+    }
+
+    pub fn magnitude(&self) -> Option<UpdateMagnitude> {
+        let current_version = Version::parse(&self.current_version).ok()?;
+        let latest_version = Version::parse(&self.latest_version).ok()?;
+
+        Some(if current_version.major != latest_version.major {
+            UpdateMagnitude::Major
+        } else if current_version.minor != latest_version.minor {
+            UpdateMagnitude::Minor
+        } else {
+            UpdateMagnitude::Patch
+        })
+    }
+
+    pub fn is_breaking_update(&self) -> bool {
+        let (Ok(requirement), Ok(latest_version)) = (
+            VersionReq::parse(&format!("^{}", self.current_version)),
+            Version::parse(&self.latest_version),
+        ) else {
+            return true;
+        };
+
+        !requirement.matches(&latest_version)
+    }
+
+    pub fn is_prerelease_update(&self) -> bool {
+        Version::parse(&self.latest_version).is_ok_and(|version| !version.pre.is_empty())
+    }
+
+    pub fn newer_versions(&self) -> Vec<&RegistryVersion> {
+        let (Ok(current_version), Ok(latest_version)) = (
+            Version::parse(&self.current_version),
+            Version::parse(&self.latest_version),
+        ) else {
+            return vec![];
+        };
+        let include_prereleases = !latest_version.pre.is_empty();
+
+        let mut versions = self
+            .versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| Some((Version::parse(&v.num).ok()?, v)))
+            .filter(|(version, _)| {
+                current_version < *version
+                    && *version <= latest_version
+                    && (include_prereleases || version.pre.is_empty())
+            })
+            .collect::<Vec<_>>();
+
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        versions.into_iter().map(|(_, v)| v).collect()
+    }
+
+    pub fn latest_rust_version(&self) -> Option<&str> {
+        self.versions
+            .iter()
+            .find(|v| v.num == self.latest_version)?
+            .rust_version
+            .as_deref()
+    }
+
+    // The `default` feature is left out since it is toggled with `default-features` instead
+    pub fn latest_features(&self) -> Vec<&str> {
+        self.versions
+            .iter()
+            .find(|v| v.num == self.latest_version)
+            .map(|v| {
+                v.features
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|feature| *feature != "default")
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn is_current_version_yanked(&self) -> bool {
+        self.versions
+            .iter()
+            .any(|v| v.yanked && v.num == self.current_version)
+    }
+
+    pub fn is_newer_version_available(&self) -> bool {
+        match (
+            Version::parse(&self.current_version),
+            Version::parse(&self.latest_version),
+        ) {
+            (Ok(current_version), Ok(latest_version)) => current_version < latest_version,
+            _ => false,
+        }
+    }
+
+    pub fn resolve_latest_version(mut self, include_prereleases: bool) -> Option<Self> {
+        // Without any known version, the crate is listed rather than passing for up to date
+        if self.unknown_source.is_some() {
+            return Some(self);
+        }
+
+        let current_version = Version::parse(&self.current_version).ok()?;
+
+        if let Some(latest) = self.latest_registry_version(include_prereleases).cloned() {
+            self.latest_version = latest.num;
+            self.latest_version_date = latest.updated_at;
+        }
+
+        let latest_version = Version::parse(&self.latest_version).ok()?;
+
+        (current_version < latest_version || self.is_current_version_yanked()).then_some(self)
+    }
+
+    // Fills in what the index doesn't list, the lookup isn't retried when it failed
+    pub fn set_details(&mut self, response: Option<CratesIoResponse>) {
+        self.details_pending = false;
+        let Some(response) = response else {
+            return;
+        };
+
+        self.repository = response.repository;
+        self.description = response.description;
+        self.current_version_date = response.current_version_date;
+        // The index listing is kept when the response lacks the versions, they drive the yanked
+        // flag, the pre-releases and the features
+        if !response.versions.is_empty() {
+            self.versions = response.versions;
+        }
+        self.latest_version_date = self
+            .versions
+            .iter()
+            .find(|v| v.num == self.latest_version)
+            .and_then(|v| v.updated_at.clone());
+    }
+
+    pub fn manifest_path(&self) -> String {
+        if self.kind == DependencyKind::Toolchain {
+            return format!("./{}", toolchain::TOOLCHAIN_FILE);
+        }
+
+        match &self.workspace_path {
+            Some(workspace_path) => format!("./{workspace_path}/Cargo.toml"),
+            None => "./Cargo.toml".to_string(),
+        }
+    }
 }
 
 impl Ord for Dependency {
@@ -36,30 +225,153 @@ impl PartialOrd for Dependency {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum UpdateMagnitude {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl UpdateMagnitude {
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateMagnitude::Major => "major",
+            UpdateMagnitude::Minor => "minor",
+            UpdateMagnitude::Patch => "patch",
+        }
+    }
+}
+
+/// How the updated version requirement is written
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, clap::ValueEnum)]
+pub enum Requirement {
+    #[default]
+    Caret,
+    Exact,
+    Tilde,
+}
+
+impl Requirement {
+    pub fn next(self) -> Self {
+        match self {
+            Requirement::Caret => Requirement::Exact,
+            Requirement::Exact => Requirement::Tilde,
+            Requirement::Tilde => Requirement::Caret,
+        }
+    }
+
+    pub fn format(self, version: &str) -> String {
+        match self {
+            Requirement::Caret => version.to_string(),
+            Requirement::Exact => format!("={version}"),
+            Requirement::Tilde => format!("~{version}"),
+        }
+    }
+}
+
+/// The manifest section declaring a dependency
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, clap::ValueEnum)]
 pub enum DependencyKind {
     #[default]
     Normal,
     Dev,
     Build,
     Workspace,
+    Toolchain,
 }
 
 impl DependencyKind {
-    pub const fn ordered() -> [DependencyKind; 4] {
+    pub const fn ordered() -> [DependencyKind; 5] {
         [
             DependencyKind::Normal,
             DependencyKind::Dev,
             DependencyKind::Build,
             DependencyKind::Workspace,
+            DependencyKind::Toolchain,
         ]
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Dev => "dev",
+            DependencyKind::Build => "build",
+            DependencyKind::Workspace => "workspace",
+            DependencyKind::Toolchain => "toolchain",
+        }
+    }
+
+    pub fn section(self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Dev => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+            DependencyKind::Workspace => "workspace.dependencies",
+            DependencyKind::Toolchain => "toolchain",
+        }
+    }
+
+    pub fn complete_order(order: &[DependencyKind]) -> Vec<DependencyKind> {
+        let mut complete_order = Vec::new();
+
+        for kind in order.iter().chain(DependencyKind::ordered().iter()) {
+            if !complete_order.contains(kind) {
+                complete_order.push(*kind);
+            }
+        }
+
+        complete_order
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, clap::ValueEnum)]
+pub enum SortOrder {
+    #[default]
+    Name,
+    Magnitude,
+    Kind,
+    Date,
+    Severity,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, clap::ValueEnum)]
+pub enum VerifyCommand {
+    #[default]
+    Check,
+    Build,
+    Test,
+    Clippy,
+    Audit,
+    None,
+}
+
+impl VerifyCommand {
+    pub fn subcommand(self) -> Option<&'static str> {
+        match self {
+            VerifyCommand::Check => Some("check"),
+            VerifyCommand::Build => Some("build"),
+            VerifyCommand::Test => Some("test"),
+            VerifyCommand::Clippy => Some("clippy"),
+            VerifyCommand::Audit => Some("audit"),
+            VerifyCommand::None => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MisalignedCrate {
+    pub name: String,
+    // Every declared version, oldest first, along with the manifests declaring it
+    pub versions: Vec<(String, Vec<String>)>,
+}
+
+/// The dependencies of a package or workspace along with their manifests
+// The manifests are shared between the copies of the dependencies, such as the outdated ones
+// and the selected ones, and only cloned once edited
 #[derive(Clone)]
 pub struct Dependencies {
     pub dependencies: Vec<Dependency>,
-    pub cargo_toml_files: HashMap<String, DocumentMut>,
+    pub cargo_toml_files: HashMap<String, Arc<DocumentMut>>,
 }
 
 impl Dependencies {
@@ -69,7 +381,10 @@ impl Dependencies {
     ) -> Self {
         Self {
             dependencies,
-            cargo_toml_files,
+            cargo_toml_files: cargo_toml_files
+                .into_iter()
+                .map(|(workspace_path, cargo_toml)| (workspace_path, Arc::new(cargo_toml)))
+                .collect(),
         }
     }
 
@@ -77,79 +392,650 @@ impl Dependencies {
         self.dependencies.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, Dependency> {
         self.dependencies.iter()
     }
 
+    // Dependencies read from the index only get their crates.io details once they are needed
+    pub fn load_details(&mut self, filter: impl Fn(&Dependency) -> bool) {
+        let indices = (0..self.dependencies.len())
+            .filter(|i| self.dependencies[*i].details_pending && filter(&self.dependencies[*i]))
+            .collect::<Vec<_>>();
+        let responses = get_details(
+            &indices
+                .iter()
+                .map(|i| &self.dependencies[*i])
+                .collect::<Vec<_>>(),
+        );
+
+        for (i, response) in indices.into_iter().zip(responses) {
+            self.dependencies[i].set_details(response);
+        }
+    }
+
+    pub fn outdated(&self, include_prereleases: bool) -> Self {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .cloned()
+            .filter_map(|d| d.resolve_latest_version(include_prereleases))
+            .collect();
+
+        Self {
+            dependencies,
+            cargo_toml_files: self.cargo_toml_files.clone(),
+        }
+    }
+
     pub fn apply_versions(&mut self, args: Args) -> Result<(), Box<dyn std::error::Error>> {
-        println!("\n\n");
+        if args.diff {
+            print!("{}", self.get_manifests_diff(args.hoist));
+            return Ok(());
+        }
+
+        logger::print("\n\n\n");
 
         if self.dependencies.is_empty() {
-            println!("No dependencies have been updated.");
+            logger::print("No dependencies have been updated.\n");
             return Ok(());
         }
 
+        args.hooks.run(Hook::PostSelect, hooks::get_payload(self))?;
+
+        let branch = args
+            .branch
+            .clone()
+            .map(|branch| branch.unwrap_or_else(git::get_default_branch_name));
+
+        if let Some(branch) = &branch {
+            git::switch_branch(branch)?;
+            logger::print(format!(
+                "Switched to branch {}.\n",
+                args.theme.emphasis(branch)
+            ));
+        }
+
+        if let Some(backup_dir) = &args.backup {
+            self.backup_manifests(backup_dir.as_deref())?;
+        }
+
+        if args.commit_per_dep {
+            for dependency in self.dependencies.clone() {
+                let mut dependencies = Dependencies {
+                    dependencies: vec![dependency],
+                    cargo_toml_files: std::mem::take(&mut self.cargo_toml_files),
+                };
+                let result = dependencies.write_and_verify(&args);
+                self.cargo_toml_files = dependencies.cargo_toml_files.clone();
+                result?;
+
+                let message = git::get_commit_message(
+                    &dependencies,
+                    &args.commit_message,
+                    &args.batch_commit_message,
+                );
+                git::commit(
+                    &git::get_changed_files(&dependencies),
+                    &git::append_changelog(message, &get_changelog(&dependencies, &args)),
+                )?;
+            }
+
+            logger::print("\nChanges have been committed, one commit per dependency.\n");
+        } else {
+            self.write_and_verify(&args)?;
+        }
+
+        if !args.no_history {
+            history::record(self)?;
+        }
+
+        let changelog = if args.commit || args.open_pr {
+            get_changelog(self, &args)
+        } else {
+            String::new()
+        };
+
+        if args.commit || (args.open_pr && !args.commit_per_dep) {
+            let message =
+                git::get_commit_message(self, &args.commit_message, &args.batch_commit_message);
+            git::commit(
+                &git::get_changed_files(self),
+                &git::append_changelog(message, &changelog),
+            )?;
+            logger::print("\nChanges have been committed.\n");
+        }
+
+        if let (true, Some(branch)) = (args.open_pr, &branch) {
+            let message =
+                git::get_commit_message(self, &args.commit_message, &args.batch_commit_message);
+            let title = message.lines().next().unwrap_or_default();
+            git::open_pull_request(
+                branch,
+                title,
+                &git::append_changelog(git::get_pull_request_body(self), &changelog),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_manifests_diff(&self, hoist: bool) -> String {
+        let mut updated = self.clone();
         for kind in DependencyKind::ordered() {
-            self.apply_versions_by_kind(kind, args.pin);
+            updated.apply_versions_by_kind(kind);
+        }
+        if hoist {
+            if let Err(e) = hoist::hoist(&mut updated) {
+                logger::warn(e);
+            }
+        }
+
+        let mut workspace_paths = self.cargo_toml_files.keys().collect::<Vec<_>>();
+        workspace_paths.sort();
+
+        workspace_paths
+            .into_iter()
+            .map(|workspace_path| {
+                let path = get_manifest_display_path(workspace_path);
+
+                diff::get_unified_diff(
+                    &path,
+                    &self.cargo_toml_files[workspace_path].to_string(),
+                    &updated.cargo_toml_files[workspace_path].to_string(),
+                )
+            })
+            .chain([toolchain::get_diff(&self.dependencies)])
+            .collect()
+    }
+
+    fn backup_manifests(
+        &self,
+        backup_dir: Option<&std::path::Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for workspace_path in self.cargo_toml_files.keys() {
+            let backup_path = get_backup_path(workspace_path, backup_dir);
+            if let Some(parent) = backup_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            logger::info(format!(
+                "Backing up {workspace_path}/Cargo.toml to {}",
+                backup_path.display()
+            ));
+            std::fs::copy(format!("{workspace_path}/Cargo.toml"), backup_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_changed_manifests(&self, previous: &HashMap<String, Arc<DocumentMut>>) -> Vec<String> {
+        let mut workspace_paths = self
+            .cargo_toml_files
+            .iter()
+            .filter(|(workspace_path, cargo_toml)| {
+                previous.get(*workspace_path).is_none_or(|previous| {
+                    !Arc::ptr_eq(previous, cargo_toml)
+                        && previous.to_string() != cargo_toml.to_string()
+                })
+            })
+            .map(|(workspace_path, _)| workspace_path.to_string())
+            .collect::<Vec<_>>();
+        workspace_paths.sort();
+        workspace_paths
+    }
+
+    // Crates declared with different versions across the members and sections
+    pub fn get_misaligned_crates(&self) -> Vec<MisalignedCrate> {
+        let mut declarations = BTreeMap::<&str, BTreeMap<&str, BTreeSet<String>>>::new();
+        for dependency in self.dependencies.iter().filter(|dependency| {
+            matches!(
+                dependency.kind,
+                DependencyKind::Normal | DependencyKind::Dev | DependencyKind::Build
+            )
+        }) {
+            declarations
+                .entry(dependency.crate_name())
+                .or_default()
+                .entry(&dependency.current_version)
+                .or_default()
+                .insert(dependency.manifest_path());
+        }
+
+        declarations
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(name, versions)| {
+                let mut versions = versions
+                    .into_iter()
+                    .map(|(version, manifests)| {
+                        (version.to_string(), manifests.into_iter().collect())
+                    })
+                    .collect::<Vec<(String, Vec<String>)>>();
+                versions.sort_by_key(|(version, _)| Version::parse(version).ok());
+
+                MisalignedCrate {
+                    name: name.to_string(),
+                    versions,
+                }
+            })
+            .collect()
+    }
+
+    // Members can declare a workspace dependency with their own version in another section, which
+    // isn't updated along with `[workspace.dependencies]` unless it was selected as well
+    fn get_conflicting_declarations(&self) -> Vec<String> {
+        let mut conflicts = vec![];
+
+        for dependency in self
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.kind == DependencyKind::Workspace)
+        {
+            for (workspace_path, cargo_toml) in self.cargo_toml_files.iter() {
+                for kind in [
+                    DependencyKind::Normal,
+                    DependencyKind::Dev,
+                    DependencyKind::Build,
+                ] {
+                    let Some(declaration) = cargo_toml
+                        .get(kind.section())
+                        .and_then(|section| section.get(&dependency.name))
+                    else {
+                        continue;
+                    };
+
+                    let is_inherited = declaration
+                        .get("workspace")
+                        .and_then(|workspace| workspace.as_bool())
+                        .unwrap_or(false);
+                    let is_selected = self.dependencies.iter().any(|selected| {
+                        selected.name == dependency.name
+                            && selected.kind == kind
+                            && selected.workspace_path.as_deref().unwrap_or(".") == workspace_path
+                    });
+
+                    if !is_inherited && !is_selected {
+                        conflicts.push(format!(
+                            "`{}` is also declared with its own version in the [{}] of {}",
+                            dependency.name,
+                            kind.section(),
+                            get_manifest_display_path(workspace_path)
+                        ));
+                    }
+                }
+            }
         }
 
-        for (workspace_path, cargo_toml) in self.cargo_toml_files.iter() {
-            std::fs::write(
-                format!("{}/Cargo.toml", workspace_path),
-                cargo_toml.to_string(),
+        conflicts.sort();
+        conflicts
+    }
+
+    fn write_manifests(
+        &self,
+        workspace_paths: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for workspace_path in workspace_paths {
+            logger::info(format!("Writing {workspace_path}/Cargo.toml"));
+            write_atomically(
+                std::path::Path::new(&format!("{workspace_path}/Cargo.toml")),
+                &self.cargo_toml_files[workspace_path].to_string(),
             )?;
-            println!("Dependencies have been updated in Cargo.toml.");
         }
 
-        if !args.no_check {
-            println!("\nExecuting {}...", "cargo check".bold());
-            std::process::Command::new("cargo").arg("check").status()?;
+        Ok(())
+    }
+
+    // The latest versions hold the versions to go back to, the lockfile is only updated for the
+    // restored crates so the rest of the dependency graph stays as it is
+    pub fn restore_versions(&mut self, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+        let original_cargo_toml_files = self.cargo_toml_files.clone();
+
+        for kind in DependencyKind::ordered() {
+            self.apply_versions_by_kind(kind);
+        }
+
+        let changed_manifests = self.get_changed_manifests(&original_cargo_toml_files);
+        self.write_manifests(&changed_manifests)?;
+        for workspace_path in changed_manifests.iter() {
+            logger::print(format!(
+                "Dependencies have been restored in {}.\n",
+                get_manifest_display_path(workspace_path)
+            ));
+        }
+        if toolchain::write(&self.dependencies)?.is_some() {
+            logger::print(format!(
+                "The toolchain has been restored in {}.\n",
+                toolchain::TOOLCHAIN_FILE
+            ));
+        }
+
+        if !args.no_lockfile && std::path::Path::new("./Cargo.lock").exists() {
+            let mut crate_names = self
+                .iter()
+                .filter(|dependency| dependency.kind != DependencyKind::Toolchain)
+                .map(|dependency| dependency.crate_name())
+                .collect::<Vec<_>>();
+            crate_names.sort();
+            crate_names.dedup();
+            update_lockfile(&crate_names, args)?;
         }
 
         Ok(())
     }
 
-    fn apply_versions_by_kind(&mut self, kind: DependencyKind, pin: bool) {
+    fn write_and_verify(&mut self, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+        // Generating the lockfile before updating lets a failed verification restore it
+        if !args.no_lockfile
+            && !args.no_generate_lockfile
+            && !std::path::Path::new("./Cargo.lock").exists()
+        {
+            generate_lockfile(args)?;
+        }
+
+        let original_cargo_toml_files = self.cargo_toml_files.clone();
+        let original_lockfile = std::fs::read_to_string("./Cargo.lock").ok();
+
+        for kind in DependencyKind::ordered() {
+            self.apply_versions_by_kind(kind);
+        }
+        let hoisted = if args.hoist {
+            hoist::hoist(self)?
+        } else {
+            vec![]
+        };
+
+        let changed_manifests = self.get_changed_manifests(&original_cargo_toml_files);
+        let mut payload = hooks::get_payload(self);
+        payload["manifests"] = changed_manifests
+            .iter()
+            .map(|workspace_path| get_manifest_display_path(workspace_path))
+            .collect();
+        args.hooks.run(Hook::PreWrite, payload)?;
+
+        self.write_manifests(&changed_manifests)?;
+        for conflict in self.get_conflicting_declarations() {
+            logger::warn(conflict);
+        }
+        for workspace_path in changed_manifests.iter() {
+            logger::print(format!(
+                "Dependencies have been updated in {}.\n",
+                get_manifest_display_path(workspace_path)
+            ));
+        }
+        for name in hoisted {
+            logger::print(format!(
+                "`{name}` has been hoisted into [workspace.dependencies].\n"
+            ));
+        }
+        let original_toolchain = toolchain::write(&self.dependencies)?;
+        if original_toolchain.is_some() {
+            logger::print(format!(
+                "The toolchain has been updated in {}.\n",
+                toolchain::TOOLCHAIN_FILE
+            ));
+        }
+
+        if args.no_lockfile {
+            logger::print(format!(
+                "\nCargo.lock has been left untouched, run {} to resolve the new versions.\n",
+                args.theme.emphasis("cargo update")
+            ));
+        } else if let Some(subcommand) = args.verify_command().subcommand() {
+            let result = run_verify_command(subcommand, args);
+            let mut payload = hooks::get_payload(self);
+            payload["command"] = format!("cargo {subcommand}").into();
+            payload["success"] = result.is_ok().into();
+            let hook_result = args.hooks.run(Hook::PostVerify, payload);
+
+            // A rejection from the hook is handled like a failed verification
+            if let Err(error) = result.and(hook_result) {
+                if args.rollback
+                    || (!args.yes
+                        && std::io::stdin().is_terminal()
+                        && confirm(
+                            "Restore the original Cargo.toml files and verify again? [Y/n] ",
+                        ))
+                {
+                    self.cargo_toml_files = original_cargo_toml_files;
+                    self.write_manifests(&changed_manifests)?;
+                    if let Some(toolchain) = &original_toolchain {
+                        write_atomically(
+                            std::path::Path::new(toolchain::TOOLCHAIN_FILE),
+                            toolchain,
+                        )?;
+                    }
+                    match original_lockfile {
+                        Some(lockfile) => {
+                            write_atomically(std::path::Path::new("./Cargo.lock"), &lockfile)?
+                        }
+                        None => {
+                            let _ = std::fs::remove_file("./Cargo.lock");
+                        }
+                    }
+                    logger::print("\nThe original Cargo.toml files have been restored.\n");
+
+                    if run_verify_command(subcommand, args).is_err() {
+                        eprintln!(
+                            "The verification also fails with the original Cargo.toml files."
+                        );
+                    }
+                }
+
+                return Err(error);
+            }
+        }
+
+        if let Some(post_cmd) = &args.post_cmd {
+            self.run_post_command(post_cmd, args.theme)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_post_command_env(&self) -> Vec<(&'static str, String)> {
+        let names = self
+            .dependencies
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect::<Vec<_>>();
+        let changes = self
+            .dependencies
+            .iter()
+            .map(|d| format!("{} {} {}", d.name, d.current_version, d.latest_version))
+            .collect::<Vec<_>>();
+
+        vec![
+            (
+                "CARGO_INTERACTIVE_UPDATE_COUNT",
+                self.dependencies.len().to_string(),
+            ),
+            ("CARGO_INTERACTIVE_UPDATE_NAMES", names.join(" ")),
+            ("CARGO_INTERACTIVE_UPDATE_CHANGES", changes.join("\n")),
+        ]
+    }
+
+    fn run_post_command(
+        &self,
+        post_cmd: &str,
+        theme: Theme,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        logger::print(format!("\nExecuting {}...\n", theme.emphasis(post_cmd)));
+        logger::info(format!("Running {post_cmd}"));
+
+        let status = get_shell_command(post_cmd)
+            .envs(self.get_post_command_env())
+            .status()?;
+
+        if !status.success() {
+            return Err(Error::Verification {
+                command: post_cmd.to_string(),
+                status: status.to_string(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn apply_versions_by_kind(&mut self, kind: DependencyKind) {
         for dependency in self.dependencies.iter().filter(|d| d.kind == kind) {
-            let cargo_toml = self
-                .cargo_toml_files
-                .get_mut(
-                    &dependency
-                        .workspace_path
-                        .clone()
-                        .unwrap_or_else(|| ".".to_string()),
-                )
-                .unwrap();
+            let cargo_toml = Arc::make_mut(
+                self.cargo_toml_files
+                    .get_mut(
+                        &dependency
+                            .workspace_path
+                            .clone()
+                            .unwrap_or_else(|| ".".to_string()),
+                    )
+                    .unwrap(),
+            );
 
-            let version = if pin {
-                value(format!("={}", dependency.latest_version))
-            } else {
-                value(&dependency.latest_version)
-            };
+            let version = dependency.requirement.format(&dependency.latest_version);
 
             let section = match kind {
                 DependencyKind::Dev => cargo_toml.get_mut("dev-dependencies"),
                 DependencyKind::Build => cargo_toml.get_mut("build-dependencies"),
                 DependencyKind::Workspace => cargo_toml["workspace"].get_mut("dependencies"),
                 DependencyKind::Normal => cargo_toml.get_mut("dependencies"),
+                // Not declared in the manifests, it is written by `toolchain::write` instead
+                DependencyKind::Toolchain => continue,
             }
             .unwrap();
 
-            if matches!(section[&dependency.name], Item::Value(Value::String(_))) {
-                section[&dependency.name] = version
+            let item = &mut section[&dependency.name];
+            if matches!(item, Item::Value(Value::String(_))) {
+                set_version(item, &version);
             } else {
-                section[&dependency.name]["version"] = version
+                set_version(&mut item["version"], &version);
             }
+
+            if let Some(features) = &dependency.features {
+                set_features(item, features);
+            }
+        }
+    }
+
+    pub fn sort(&mut self, section_order: &[DependencyKind], sort: SortOrder) {
+        let position = |kind: DependencyKind| {
+            section_order
+                .iter()
+                .position(|k| *k == kind)
+                .unwrap_or(section_order.len())
+        };
+
+        self.dependencies.sort_by(|a, b| {
+            position(a.kind)
+                .cmp(&position(b.kind))
+                .then_with(|| a.held_back.is_some().cmp(&b.held_back.is_some()))
+                .then_with(|| match sort {
+                    SortOrder::Name => std::cmp::Ordering::Equal,
+                    SortOrder::Kind => a.kind.cmp(&b.kind),
+                    SortOrder::Magnitude => a.magnitude().cmp(&b.magnitude()),
+                    SortOrder::Date => b.latest_version_date.cmp(&a.latest_version_date),
+                    SortOrder::Severity => {
+                        security::get_severity(b).cmp(&security::get_severity(a))
+                    }
+                })
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.workspace_path.cmp(&b.workspace_path))
+        });
+    }
+
+    pub fn set_requirement(&mut self, requirement: Requirement) {
+        for dependency in self.dependencies.iter_mut() {
+            dependency.requirement = requirement;
+        }
+    }
+
+    pub fn set_precise_version(self, name: &str, version: &str) -> Result<Self, String> {
+        let selected = self
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.crate_name() == name)
+            .collect::<Vec<_>>();
+
+        if !selected.contains(&true) {
+            return Err(format!("dependency `{name}` was not found"));
+        }
+
+        let mut dependencies = self.filter_selected_dependencies(selected);
+
+        for dependency in dependencies.dependencies.iter_mut() {
+            if dependency.versions.is_empty() {
+                return Err(format!(
+                    "the versions of `{name}` are unavailable, {version} can't be checked"
+                ));
+            }
+            if !dependency.versions.iter().any(|v| v.num == version) {
+                return Err(format!(
+                    "version {version} of `{name}` was not found on crates.io"
+                ));
+            }
+
+            dependency.latest_version = version.to_string();
         }
+
+        Ok(dependencies)
     }
 
     pub fn has_workspace_members(&self) -> bool {
         self.dependencies.iter().any(|d| d.workspace_path.is_some())
     }
 
+    pub fn get_declared_features(&self, dependency: &Dependency) -> Vec<String> {
+        let Some(cargo_toml) = self
+            .cargo_toml_files
+            .get(dependency.workspace_path.as_deref().unwrap_or("."))
+        else {
+            return vec![];
+        };
+
+        let section = match dependency.kind {
+            DependencyKind::Workspace => cargo_toml
+                .get("workspace")
+                .and_then(|workspace| workspace.get("dependencies")),
+            kind => cargo_toml.get(kind.section()),
+        };
+
+        section
+            .and_then(|section| section.get(&dependency.name))
+            .and_then(|item| item.get("features"))
+            .and_then(|features| features.as_array())
+            .map(|features| {
+                features
+                    .iter()
+                    .filter_map(|feature| feature.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Spans are dropped once a document is editable, so the manifest is parsed again to find the
+    // line declaring the dependency
+    pub fn get_declaration_line(&self, dependency: &Dependency) -> Option<usize> {
+        let content = self
+            .cargo_toml_files
+            .get(dependency.workspace_path.as_deref().unwrap_or("."))?
+            .to_string();
+        let document = toml_edit::ImDocument::parse(content.as_str()).ok()?;
+
+        let section = match dependency.kind {
+            DependencyKind::Workspace => document.get("workspace")?.get("dependencies")?,
+            DependencyKind::Toolchain => return None,
+            kind => document.get(kind.section())?,
+        };
+        let (key, _) = section.as_table_like()?.get_key_value(&dependency.name)?;
+
+        Some(content[..key.span()?.start].matches('\n').count() + 1)
+    }
+
     pub fn filter_selected_dependencies(self, selected: Vec<bool>) -> Self {
         let mut workspace_paths = HashSet::new();
-        let dependencies = self
+        let dependencies: Vec<_> = self
             .dependencies
             .into_iter()
             .zip(selected.iter())
@@ -160,10 +1046,31 @@ impl Dependencies {
             })
             .collect();
 
+        // The members declaring a selected workspace dependency are kept for the conflict check,
+        // they are only written when changed
+        let workspace_names = dependencies
+            .iter()
+            .filter(|dependency| dependency.kind == DependencyKind::Workspace)
+            .map(|dependency| dependency.name.as_str())
+            .collect::<HashSet<_>>();
         let cargo_toml_files = self
             .cargo_toml_files
             .into_iter()
-            .filter(|(workspace_path, _)| workspace_paths.contains(workspace_path))
+            .filter(|(workspace_path, cargo_toml)| {
+                workspace_paths.contains(workspace_path)
+                    || [
+                        DependencyKind::Normal,
+                        DependencyKind::Dev,
+                        DependencyKind::Build,
+                    ]
+                    .into_iter()
+                    .filter_map(|kind| cargo_toml.get(kind.section())?.as_table_like())
+                    .any(|section| {
+                        workspace_names
+                            .iter()
+                            .any(|name| section.contains_key(name))
+                    })
+            })
             .collect();
 
         Self {
@@ -181,3 +1088,1056 @@ impl IntoIterator for Dependencies {
         self.dependencies.into_iter()
     }
 }
+
+fn get_backup_path(
+    workspace_path: &str,
+    backup_dir: Option<&std::path::Path>,
+) -> std::path::PathBuf {
+    match backup_dir {
+        Some(backup_dir) => backup_dir.join(workspace_path).join("Cargo.toml"),
+        None => std::path::Path::new(workspace_path).join("Cargo.toml.bak"),
+    }
+}
+
+// The crates.io responses of the dependencies, a failed lookup only leaving their details out
+pub fn get_details(dependencies: &[&Dependency]) -> Vec<Option<CratesIoResponse>> {
+    let lookups = dependencies
+        .iter()
+        .map(|dependency| CargoDependency {
+            name: dependency.name.clone(),
+            version: dependency.current_version.clone(),
+            kind: dependency.kind,
+            package: dependency.package.clone(),
+            registry: None,
+        })
+        .collect::<Vec<_>>();
+
+    cargo::get_latest_versions(lookups.iter().collect())
+        .into_iter()
+        .zip(dependencies)
+        .map(|(response, dependency)| {
+            response
+                .map_err(|e| {
+                    logger::warn(format!(
+                        "Unable to fetch the details of `{}`: {e}",
+                        dependency.name
+                    ))
+                })
+                .ok()
+        })
+        .collect()
+}
+
+// Only the value is replaced, keeping its surrounding whitespace and comments and the position of
+// the key within its table
+pub fn set_version(item: &mut Item, version: &str) {
+    let Some(current) = item.as_value_mut() else {
+        *item = value(version);
+        return;
+    };
+
+    let decor = current.decor().clone();
+    *current = Value::from(version);
+    *current.decor_mut() = decor;
+}
+
+// Plain version strings become inline tables once a feature is enabled, and the array is removed
+// when every feature is disabled
+fn set_features(item: &mut Item, features: &[String]) {
+    if let Some(Value::String(version)) = item.as_value() {
+        if features.is_empty() {
+            return;
+        }
+
+        let mut table = toml_edit::InlineTable::new();
+        table.insert("version", Value::from(version.value().as_str()));
+        let mut updated = Value::InlineTable(table);
+        *updated.decor_mut() = version.decor().clone();
+        *item = Item::Value(updated);
+    }
+
+    if features.is_empty() {
+        remove_features(item);
+        return;
+    }
+
+    let Some(table) = item.as_table_like_mut() else {
+        return;
+    };
+
+    let array = features
+        .iter()
+        .map(String::as_str)
+        .collect::<toml_edit::Array>();
+    match table.get_mut("features").and_then(Item::as_value_mut) {
+        Some(current) => {
+            let decor = current.decor().clone();
+            *current = Value::Array(array);
+            *current.decor_mut() = decor;
+        }
+        None => {
+            table.insert("features", value(array));
+        }
+    }
+}
+
+// The spacing before the closing brace of an inline table belongs to its last value, so it is moved
+// to the new last value
+fn remove_features(item: &mut Item) {
+    if let Some(table) = item.as_inline_table_mut() {
+        let is_last = table
+            .iter()
+            .last()
+            .is_some_and(|(key, _)| key == "features");
+        let suffix = table
+            .remove("features")
+            .and_then(|features| features.decor().suffix().cloned());
+
+        if let (true, Some(suffix), Some((_, last))) = (is_last, suffix, table.iter_mut().last()) {
+            last.decor_mut().set_suffix(suffix);
+        }
+    } else if let Some(table) = item.as_table_like_mut() {
+        table.remove("features");
+    }
+}
+
+fn get_manifest_display_path(workspace_path: &str) -> String {
+    match workspace_path {
+        "." => "Cargo.toml".to_string(),
+        _ => format!("{workspace_path}/Cargo.toml"),
+    }
+}
+
+// Writing to a temporary file in the same directory before renaming it over the original means an
+// interrupted run leaves either the old or the new content, never a truncated file. Symlinks are
+// resolved so their target is replaced instead of the link, keeping the original permissions
+pub fn write_atomically(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut temporary_name = path.file_name().unwrap_or_default().to_os_string();
+    temporary_name.push(format!(".{}.tmp", std::process::id()));
+    let temporary_path = path.with_file_name(temporary_name);
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&temporary_path)?;
+        std::io::Write::write_all(&mut file, content.as_bytes())?;
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&temporary_path, &path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+    }
+
+    result
+}
+
+fn generate_lockfile(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    logger::print(format!(
+        "\nCargo.lock is missing, executing {}...\n",
+        args.theme.emphasis("cargo generate-lockfile")
+    ));
+
+    let mut command = vec!["generate-lockfile"];
+    command.extend(
+        args.cargo_flags()
+            .into_iter()
+            .filter(|flag| matches!(*flag, "--offline" | "--quiet")),
+    );
+    logger::info(format!("Running cargo {}", command.join(" ")));
+
+    let status = std::process::Command::new("cargo")
+        .args(&command)
+        .status()?;
+    if !status.success() {
+        return Err(format!("`cargo generate-lockfile` failed with {status}").into());
+    }
+
+    Ok(())
+}
+
+fn update_lockfile(crate_names: &[&str], args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = vec!["update"];
+    for crate_name in crate_names {
+        command.extend(["--package", crate_name]);
+    }
+    command.extend(
+        args.cargo_flags()
+            .into_iter()
+            .filter(|flag| matches!(*flag, "--offline" | "--quiet")),
+    );
+    logger::info(format!("Running cargo {}", command.join(" ")));
+
+    let status = std::process::Command::new("cargo")
+        .args(&command)
+        .status()?;
+    if !status.success() {
+        return Err(format!("`cargo update` failed with {status}").into());
+    }
+
+    Ok(())
+}
+
+fn run_verify_command(subcommand: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = vec!["cargo", subcommand];
+    // `cargo audit` only reads the lockfile, it has none of the other flags
+    command.extend(
+        args.cargo_flags()
+            .into_iter()
+            .filter(|flag| subcommand != "audit" || *flag == "--quiet"),
+    );
+    let command_line = command.join(" ");
+
+    logger::print(format!(
+        "\nExecuting {}...\n",
+        args.theme.emphasis(format!("cargo {subcommand}"))
+    ));
+    logger::info(format!("Running {command_line}"));
+    let status = std::process::Command::new(command[0])
+        .args(&command[1..])
+        .status()?;
+
+    if !status.success() {
+        return Err(Error::Verification {
+            command: command_line,
+            status: status.to_string(),
+        }
+        .into());
+    }
+
+    if args.semver_checks {
+        semver_checks::run(args)?;
+    }
+
+    Ok(())
+}
+
+fn get_changelog(dependencies: &Dependencies, args: &Args) -> String {
+    if args.no_changelog {
+        return String::new();
+    }
+
+    logger::info("Fetching the release notes of the updated dependencies");
+    changelog::get_changelog(dependencies)
+}
+
+pub fn get_shell_command(command_line: &str) -> std::process::Command {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(command_line);
+    command
+}
+
+fn confirm(question: &str) -> bool {
+    eprint!("{question}");
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok() && is_confirmed(&answer)
+}
+
+fn is_confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_path() {
+        let dependency = Dependency::default();
+        assert_eq!(dependency.manifest_path(), "./Cargo.toml");
+
+        let dependency = Dependency {
+            workspace_path: Some("crates/foo".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(dependency.manifest_path(), "./crates/foo/Cargo.toml");
+    }
+
+    #[test]
+    fn test_get_declaration_line() {
+        let cargo_toml = "[workspace.dependencies]\nlog = \"0.4\"\n\n[dependencies]\nserde = \"1.0\"\n\n[dev-dependencies.rand]\nversion = \"0.8\"\n";
+        let dependencies = Dependencies::new(
+            vec![],
+            HashMap::from([(".".to_string(), cargo_toml.parse().unwrap())]),
+        );
+        let dependency = |name: &str, kind| Dependency {
+            name: name.to_string(),
+            kind,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            dependencies.get_declaration_line(&dependency("log", DependencyKind::Workspace)),
+            Some(2)
+        );
+        assert_eq!(
+            dependencies.get_declaration_line(&dependency("serde", DependencyKind::Normal)),
+            Some(5)
+        );
+        assert_eq!(
+            dependencies.get_declaration_line(&dependency("rand", DependencyKind::Dev)),
+            Some(7)
+        );
+        assert_eq!(
+            dependencies.get_declaration_line(&dependency("serde", DependencyKind::Dev)),
+            None
+        );
+    }
+
+    fn registry_version(num: &str, yanked: bool) -> RegistryVersion {
+        RegistryVersion {
+            num: num.to_string(),
+            updated_at: Some(format!("{num}-date")),
+            yanked,
+            rust_version: None,
+            features: vec![],
+        }
+    }
+
+    #[test]
+    fn test_resolve_latest_version() {
+        let dependency = Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            versions: vec![
+                registry_version("1.0.0", false),
+                registry_version("1.1.0", false),
+                registry_version("1.2.0", true),
+                registry_version("2.0.0-beta.1", false),
+            ],
+            ..Default::default()
+        };
+
+        let stable = dependency.clone().resolve_latest_version(false).unwrap();
+        assert_eq!(stable.latest_version, "1.1.0");
+        assert_eq!(stable.latest_version_date, Some("1.1.0-date".to_string()));
+
+        let prerelease = dependency.resolve_latest_version(true).unwrap();
+        assert_eq!(prerelease.latest_version, "2.0.0-beta.1");
+    }
+
+    #[test]
+    fn test_set_details() {
+        let version = |num: &str, updated_at: Option<&str>| RegistryVersion {
+            num: num.to_string(),
+            updated_at: updated_at.map(str::to_string),
+            ..Default::default()
+        };
+        let mut dependency = Dependency {
+            latest_version: "1.1.0".to_string(),
+            versions: vec![version("1.1.0", None), version("1.0.0", None)],
+            details_pending: true,
+            ..Default::default()
+        };
+        let response = |versions| CratesIoResponse {
+            repository: Some("https://github.com/serde-rs/serde".to_string()),
+            description: None,
+            latest_version: "1.1.0".to_string(),
+            latest_version_date: None,
+            current_version_date: None,
+            versions,
+        };
+
+        dependency.set_details(Some(response(vec![])));
+        assert!(!dependency.details_pending);
+        assert_eq!(dependency.versions.len(), 2);
+        assert!(dependency.repository.is_some());
+
+        dependency.set_details(Some(response(vec![version(
+            "1.1.0",
+            Some("2024-01-01T00:00:00Z"),
+        )])));
+        assert_eq!(dependency.versions.len(), 1);
+        assert_eq!(
+            dependency.latest_version_date.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_resolve_latest_version_up_to_date() {
+        let dependency = Dependency {
+            current_version: "1.1.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            versions: vec![
+                registry_version("1.1.0", false),
+                registry_version("1.2.0-rc.1", false),
+            ],
+            ..Default::default()
+        };
+
+        assert!(dependency.clone().resolve_latest_version(false).is_none());
+        assert!(dependency.resolve_latest_version(true).is_some());
+    }
+
+    #[test]
+    fn test_resolve_latest_version_with_yanked_current_version() {
+        let dependency = Dependency {
+            current_version: "1.2.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            versions: vec![
+                registry_version("1.1.0", false),
+                registry_version("1.2.0", true),
+            ],
+            ..Default::default()
+        };
+
+        assert!(dependency.is_current_version_yanked());
+
+        let dependency = dependency.resolve_latest_version(false).unwrap();
+        assert_eq!(dependency.latest_version, "1.1.0");
+    }
+
+    #[test]
+    fn test_resolve_latest_version_without_versions() {
+        let dependency = Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            ..Default::default()
+        };
+
+        let dependency = dependency.resolve_latest_version(true).unwrap();
+        assert_eq!(dependency.latest_version, "1.1.0");
+    }
+
+    #[test]
+    fn test_resolve_latest_version_with_unknown_source() {
+        let dependency = Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.0.0".to_string(),
+            unknown_source: Some("not found on crates.io".to_string()),
+            ..Default::default()
+        };
+
+        let dependency = dependency.resolve_latest_version(false).unwrap();
+        assert_eq!(dependency.latest_version, "1.0.0");
+    }
+
+    #[test]
+    fn test_newer_versions() {
+        let dependency = Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.2.0".to_string(),
+            versions: vec![
+                registry_version("1.3.0", false),
+                registry_version("1.2.0", false),
+                registry_version("1.2.0-rc.1", false),
+                registry_version("1.1.1", true),
+                registry_version("1.1.0", false),
+                registry_version("1.0.0", false),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            dependency
+                .newer_versions()
+                .iter()
+                .map(|v| v.num.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1.1.0", "1.2.0"]
+        );
+    }
+
+    #[test]
+    fn test_magnitude() {
+        for (current_version, latest_version, magnitude) in [
+            ("1.0.0", "2.0.0", Some(UpdateMagnitude::Major)),
+            ("1.0.0", "1.1.0", Some(UpdateMagnitude::Minor)),
+            ("1.0.0", "1.0.1", Some(UpdateMagnitude::Patch)),
+            ("1.0.0-alpha.1", "1.0.0", Some(UpdateMagnitude::Patch)),
+            ("1.0", "1.0.1", None),
+        ] {
+            let dependency = Dependency {
+                current_version: current_version.to_string(),
+                latest_version: latest_version.to_string(),
+                ..Default::default()
+            };
+            assert_eq!(dependency.magnitude(), magnitude);
+        }
+    }
+
+    #[test]
+    fn test_is_prerelease_update() {
+        let dependency = Dependency {
+            latest_version: "2.0.0-beta.1".to_string(),
+            ..Default::default()
+        };
+        assert!(dependency.is_prerelease_update());
+
+        let dependency = Dependency {
+            latest_version: "2.0.0".to_string(),
+            ..Default::default()
+        };
+        assert!(!dependency.is_prerelease_update());
+    }
+
+    #[test]
+    fn test_complete_order() {
+        assert_eq!(
+            DependencyKind::complete_order(&[DependencyKind::Workspace, DependencyKind::Dev]),
+            vec![
+                DependencyKind::Workspace,
+                DependencyKind::Dev,
+                DependencyKind::Normal,
+                DependencyKind::Build,
+                DependencyKind::Toolchain,
+            ]
+        );
+        assert_eq!(
+            DependencyKind::complete_order(&[]),
+            DependencyKind::ordered().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_sort() {
+        let dependency = |name: &str, kind, latest_version: &str, date: &str| Dependency {
+            name: name.to_string(),
+            kind,
+            current_version: "1.0.0".to_string(),
+            latest_version: latest_version.to_string(),
+            latest_version_date: Some(date.to_string()),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("a", DependencyKind::Normal, "1.0.1", "2024-01-03"),
+                dependency("b", DependencyKind::Normal, "2.0.0", "2024-01-01"),
+                dependency("c", DependencyKind::Normal, "1.1.0", "2024-01-02"),
+                dependency("d", DependencyKind::Workspace, "1.0.1", "2024-01-01"),
+            ],
+            HashMap::new(),
+        );
+        let names = |dependencies: &Dependencies| {
+            dependencies
+                .iter()
+                .map(|d| d.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let section_order = DependencyKind::complete_order(&[DependencyKind::Workspace]);
+
+        dependencies.sort(&section_order, SortOrder::Name);
+        assert_eq!(names(&dependencies), vec!["d", "a", "b", "c"]);
+
+        dependencies.sort(&section_order, SortOrder::Magnitude);
+        assert_eq!(names(&dependencies), vec!["d", "b", "c", "a"]);
+
+        dependencies.sort(&section_order, SortOrder::Date);
+        assert_eq!(names(&dependencies), vec!["d", "a", "c", "b"]);
+
+        dependencies.sort(&[], SortOrder::Magnitude);
+        assert_eq!(names(&dependencies), vec!["b", "c", "a", "d"]);
+
+        dependencies.sort(&[], SortOrder::Kind);
+        assert_eq!(names(&dependencies), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_sort_held_back_last() {
+        let mut dependencies = Dependencies::new(
+            vec![
+                Dependency {
+                    name: "a".to_string(),
+                    held_back: Some("ignored".to_string()),
+                    ..Default::default()
+                },
+                Dependency {
+                    name: "b".to_string(),
+                    ..Default::default()
+                },
+            ],
+            HashMap::new(),
+        );
+
+        dependencies.sort(&DependencyKind::ordered(), SortOrder::Name);
+        assert_eq!(dependencies.dependencies[0].name, "b");
+        assert_eq!(dependencies.dependencies[1].name, "a");
+    }
+
+    #[test]
+    fn test_requirement_cycle() {
+        let requirement = Requirement::default();
+        assert_eq!(requirement, Requirement::Caret);
+        assert_eq!(requirement.next(), Requirement::Exact);
+        assert_eq!(requirement.next().next(), Requirement::Tilde);
+        assert_eq!(requirement.next().next().next(), Requirement::Caret);
+    }
+
+    #[test]
+    fn test_requirement_format() {
+        assert_eq!(Requirement::Caret.format("1.2.3"), "1.2.3");
+        assert_eq!(Requirement::Exact.format("1.2.3"), "=1.2.3");
+        assert_eq!(Requirement::Tilde.format("1.2.3"), "~1.2.3");
+    }
+
+    #[test]
+    fn test_get_manifests_diff() {
+        let dependencies = Dependencies::new(
+            vec![Dependency {
+                name: "serde".to_string(),
+                current_version: "1.0.0".to_string(),
+                latest_version: "1.0.1".to_string(),
+                workspace_path: Some("crates/foo".to_string()),
+                ..Default::default()
+            }],
+            HashMap::from_iter([
+                (
+                    "crates/foo".to_string(),
+                    "[dependencies]\nserde = { version = \"1.0.0\", features = [\"derive\"] }\n"
+                        .parse()
+                        .unwrap(),
+                ),
+                (".".to_string(), "[workspace]\n".parse().unwrap()),
+            ]),
+        );
+
+        assert_eq!(
+            dependencies.get_manifests_diff(false),
+            [
+                "--- a/crates/foo/Cargo.toml",
+                "+++ b/crates/foo/Cargo.toml",
+                "@@ -1,2 +1,2 @@",
+                " [dependencies]",
+                "-serde = { version = \"1.0.0\", features = [\"derive\"] }",
+                "+serde = { version = \"1.0.1\", features = [\"derive\"] }",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_apply_versions_by_kind_preserves_formatting() {
+        const CARGO_TOML: &str = r#"[dependencies]
+# Serialization
+serde = "1.0.0" # keep in sync with serde_json
+log = { version = "0.4.0", features = ["std"] } # logging
+
+[dependencies.tokio]
+# runtime
+features = ["full"] # everything
+version  =  "1.0.0"   # lts
+default-features = false
+"#;
+
+        let dependency = |name: &str, latest_version: &str| Dependency {
+            name: name.to_string(),
+            latest_version: latest_version.to_string(),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("serde", "1.0.200"),
+                dependency("log", "0.4.22"),
+                dependency("tokio", "1.40.0"),
+            ],
+            HashMap::from_iter([(".".to_string(), CARGO_TOML.parse().unwrap())]),
+        );
+        dependencies.apply_versions_by_kind(DependencyKind::Normal);
+
+        assert_eq!(
+            dependencies.cargo_toml_files["."].to_string(),
+            CARGO_TOML
+                .replace("1.0.0\" #", "1.0.200\" #")
+                .replace("0.4.0", "0.4.22")
+                .replace("1.0.0\"   #", "1.40.0\"   #")
+        );
+    }
+
+    #[test]
+    fn test_apply_versions_by_kind_with_workspace_tables() {
+        const CARGO_TOML: &str = r#"[workspace.dependencies]
+serde = { version = "1.0.0", features = ["derive"], default-features = false }
+json = { package = "serde_json", version = "1.0.100" }
+
+[workspace.dependencies.toml]
+package = "toml_edit"
+version = "0.22.0"
+features = ["serde"]
+"#;
+
+        let dependency = |name: &str, package: Option<&str>, latest_version: &str| Dependency {
+            name: name.to_string(),
+            package: package.map(|package| package.to_string()),
+            latest_version: latest_version.to_string(),
+            kind: DependencyKind::Workspace,
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("serde", None, "1.0.200"),
+                dependency("json", Some("serde_json"), "1.0.128"),
+                dependency("toml", Some("toml_edit"), "0.22.22"),
+            ],
+            HashMap::from_iter([(".".to_string(), CARGO_TOML.parse().unwrap())]),
+        );
+        dependencies.apply_versions_by_kind(DependencyKind::Workspace);
+
+        assert_eq!(
+            dependencies.cargo_toml_files["."].to_string(),
+            CARGO_TOML
+                .replace("1.0.0", "1.0.200")
+                .replace("1.0.100", "1.0.128")
+                .replace("0.22.0", "0.22.22")
+        );
+    }
+
+    #[test]
+    fn test_apply_versions_by_kind_with_features() {
+        const CARGO_TOML: &str = r#"[dependencies]
+serde = "1.0.0" # serialization
+log = { version = "0.4.0", features = ["std"] }
+rand = { version = "0.8.0", features = ["small_rng"] }
+
+[dependencies.tokio]
+version = "1.0.0"
+"#;
+
+        let dependency = |name: &str, features: &[&str]| Dependency {
+            name: name.to_string(),
+            latest_version: "2.0.0".to_string(),
+            features: Some(features.iter().map(|f| f.to_string()).collect()),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("serde", &["derive"]),
+                dependency("log", &["std", "kv"]),
+                dependency("rand", &[]),
+                dependency("tokio", &["full"]),
+            ],
+            HashMap::from_iter([(".".to_string(), CARGO_TOML.parse().unwrap())]),
+        );
+        assert_eq!(
+            dependencies.get_declared_features(&dependencies.dependencies[1]),
+            vec!["std".to_string()]
+        );
+
+        dependencies.apply_versions_by_kind(DependencyKind::Normal);
+
+        assert_eq!(
+            dependencies.cargo_toml_files["."].to_string(),
+            r#"[dependencies]
+serde = { version = "2.0.0", features = ["derive"] } # serialization
+log = { version = "2.0.0", features = ["std", "kv"] }
+rand = { version = "2.0.0" }
+
+[dependencies.tokio]
+version = "2.0.0"
+features = ["full"]
+"#
+        );
+    }
+
+    #[test]
+    fn test_get_conflicting_declarations() {
+        let dependency =
+            |name: &str, kind: DependencyKind, workspace_path: Option<&str>| Dependency {
+                name: name.to_string(),
+                kind,
+                workspace_path: workspace_path.map(|path| path.to_string()),
+                ..Default::default()
+            };
+        let cargo_toml_files = HashMap::from_iter([
+            (
+                ".".to_string(),
+                "[workspace.dependencies]\nserde = \"1.0.0\"\ntokio = \"1.0.0\""
+                    .parse()
+                    .unwrap(),
+            ),
+            (
+                "crates/foo".to_string(),
+                r#"
+                [dependencies]
+                serde = { workspace = true }
+                tokio = { workspace = true }
+
+                [dev-dependencies]
+                serde = "1.0.0"
+                tokio = { version = "1.0.0", features = ["full"] }
+                "#
+                .parse()
+                .unwrap(),
+            ),
+        ]);
+
+        let dependencies = Dependencies::new(
+            vec![
+                dependency("serde", DependencyKind::Workspace, None),
+                dependency("tokio", DependencyKind::Workspace, None),
+                dependency("tokio", DependencyKind::Dev, Some("crates/foo")),
+            ],
+            cargo_toml_files,
+        );
+
+        assert_eq!(
+            dependencies.get_conflicting_declarations(),
+            vec![
+                "`serde` is also declared with its own version in the [dev-dependencies] of crates/foo/Cargo.toml"
+                    .to_string()
+            ]
+        );
+
+        // Selecting only the workspace entry keeps the member declaring its own version
+        let selected = dependencies.filter_selected_dependencies(vec![true, false, false]);
+        assert_eq!(selected.cargo_toml_files.len(), 2);
+        assert_eq!(
+            selected.get_conflicting_declarations(),
+            vec![
+                "`serde` is also declared with its own version in the [dev-dependencies] of crates/foo/Cargo.toml"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_misaligned_crates() {
+        let dependency =
+            |name: &str, kind, version: &str, workspace_path: Option<&str>| Dependency {
+                name: name.to_string(),
+                kind,
+                current_version: version.to_string(),
+                workspace_path: workspace_path.map(|path| path.to_string()),
+                ..Default::default()
+            };
+        let dependencies = Dependencies::new(
+            vec![
+                dependency("serde", DependencyKind::Normal, "1.0.180", Some("crates/a")),
+                dependency("serde", DependencyKind::Dev, "1.0.9", Some("crates/b")),
+                dependency("serde", DependencyKind::Normal, "1.0.180", None),
+                dependency("tokio", DependencyKind::Normal, "1.0.0", Some("crates/a")),
+                dependency("tokio", DependencyKind::Workspace, "1.2.0", None),
+            ],
+            HashMap::new(),
+        );
+
+        assert_eq!(
+            dependencies.get_misaligned_crates(),
+            vec![MisalignedCrate {
+                name: "serde".to_string(),
+                versions: vec![
+                    (
+                        "1.0.9".to_string(),
+                        vec!["./crates/b/Cargo.toml".to_string()]
+                    ),
+                    (
+                        "1.0.180".to_string(),
+                        vec![
+                            "./Cargo.toml".to_string(),
+                            "./crates/a/Cargo.toml".to_string()
+                        ]
+                    ),
+                ]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_backup_path() {
+        assert_eq!(
+            get_backup_path(".", None),
+            std::path::PathBuf::from("./Cargo.toml.bak")
+        );
+        assert_eq!(
+            get_backup_path("crates/foo", None),
+            std::path::PathBuf::from("crates/foo/Cargo.toml.bak")
+        );
+        assert_eq!(
+            get_backup_path("crates/foo", Some(std::path::Path::new("backup"))),
+            std::path::PathBuf::from("backup/crates/foo/Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn test_get_post_command_env() {
+        let dependency = |name: &str, current_version: &str, latest_version: &str| Dependency {
+            name: name.to_string(),
+            current_version: current_version.to_string(),
+            latest_version: latest_version.to_string(),
+            ..Default::default()
+        };
+        let dependencies = Dependencies::new(
+            vec![
+                dependency("serde", "1.0.0", "1.0.1"),
+                dependency("tokio", "1.0.0", "1.2.0"),
+            ],
+            HashMap::new(),
+        );
+
+        assert_eq!(
+            dependencies.get_post_command_env(),
+            vec![
+                ("CARGO_INTERACTIVE_UPDATE_COUNT", "2".to_string()),
+                ("CARGO_INTERACTIVE_UPDATE_NAMES", "serde tokio".to_string()),
+                (
+                    "CARGO_INTERACTIVE_UPDATE_CHANGES",
+                    "serde 1.0.0 1.0.1\ntokio 1.0.0 1.2.0".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_precise_version() {
+        let dependency = |name: &str, workspace_path: Option<&str>| Dependency {
+            name: name.to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.0.0".to_string(),
+            workspace_path: workspace_path.map(|path| path.to_string()),
+            versions: vec![
+                registry_version("1.0.1", false),
+                registry_version("1.0.0", false),
+            ],
+            ..Default::default()
+        };
+        let dependencies = || {
+            Dependencies::new(
+                vec![
+                    dependency("serde", None),
+                    dependency("log", None),
+                    dependency("serde", Some("crates/foo")),
+                ],
+                HashMap::from_iter([
+                    (".".to_string(), DocumentMut::new()),
+                    ("crates/foo".to_string(), DocumentMut::new()),
+                ]),
+            )
+        };
+
+        let precise = dependencies()
+            .set_precise_version("serde", "1.0.1")
+            .unwrap();
+        assert_eq!(precise.len(), 2);
+        assert!(precise
+            .iter()
+            .all(|d| d.name == "serde" && d.latest_version == "1.0.1"));
+        assert_eq!(precise.cargo_toml_files.len(), 2);
+
+        assert_eq!(
+            dependencies().set_precise_version("tokio", "1.0.1").err(),
+            Some("dependency `tokio` was not found".to_string())
+        );
+        assert_eq!(
+            dependencies().set_precise_version("serde", "2.0.0").err(),
+            Some("version 2.0.0 of `serde` was not found on crates.io".to_string())
+        );
+
+        let renamed = Dependencies::new(
+            vec![Dependency {
+                name: "json".to_string(),
+                package: Some("serde_json".to_string()),
+                ..dependency("json", None)
+            }],
+            HashMap::from_iter([(".".to_string(), DocumentMut::new())]),
+        );
+        let precise = renamed.set_precise_version("serde_json", "1.0.1").unwrap();
+        assert_eq!(precise.dependencies[0].latest_version, "1.0.1");
+
+        let unavailable = Dependencies::new(
+            vec![Dependency {
+                versions: vec![],
+                ..dependency("serde", None)
+            }],
+            HashMap::from_iter([(".".to_string(), DocumentMut::new())]),
+        );
+        assert_eq!(
+            unavailable.set_precise_version("serde", "1.0.1").err(),
+            Some("the versions of `serde` are unavailable, 1.0.1 can't be checked".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_changed_manifests() {
+        let cargo_toml = |content: &str| Arc::new(content.parse::<DocumentMut>().unwrap());
+        let previous = HashMap::from([
+            (
+                ".".to_string(),
+                cargo_toml("[dependencies]\nserde = \"1.0.0\""),
+            ),
+            (
+                "foo".to_string(),
+                cargo_toml("[dependencies]\nlog = \"0.4.0\""),
+            ),
+        ]);
+        let mut dependencies = Dependencies {
+            dependencies: vec![],
+            cargo_toml_files: previous.clone(),
+        };
+        assert!(dependencies.get_changed_manifests(&previous).is_empty());
+
+        dependencies.cargo_toml_files.insert(
+            "foo".to_string(),
+            cargo_toml("[dependencies]\nlog = \"0.4.22\""),
+        );
+        dependencies
+            .cargo_toml_files
+            .insert("bar".to_string(), cargo_toml(""));
+        assert_eq!(
+            dependencies.get_changed_manifests(&previous),
+            vec!["bar".to_string(), "foo".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = std::env::temp_dir().join(format!(
+            "cargo-interactive-update-{}-atomic",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Cargo.toml");
+
+        std::fs::write(&path, "[dependencies]\nserde = \"1.0.0\"\n").unwrap();
+        write_atomically(&path, "[dependencies]\nserde = \"1.0.1\"\n").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[dependencies]\nserde = \"1.0.1\"\n"
+        );
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(write_atomically(&dir.join("missing/Cargo.toml"), "").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+            let link = dir.join("Link.toml");
+            std::os::unix::fs::symlink(&path, &link).unwrap();
+            write_atomically(&link, "[dependencies]\nserde = \"1.0.2\"\n").unwrap();
+
+            assert!(std::fs::symlink_metadata(&link).unwrap().is_symlink());
+            assert_eq!(
+                std::fs::read_to_string(&path).unwrap(),
+                "[dependencies]\nserde = \"1.0.2\"\n"
+            );
+            assert_eq!(
+                std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+                0o600
+            );
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_confirmed() {
+        assert!(is_confirmed("\n"));
+        assert!(is_confirmed("y\n"));
+        assert!(is_confirmed(" Yes \n"));
+        assert!(!is_confirmed("n\n"));
+        assert!(!is_confirmed("nope\n"));
+    }
+}
@@ -0,0 +1,104 @@
+const CONTEXT_LINES: usize = 3;
+
+// Updating versions never adds or removes lines, so lines are compared one to one and a single
+// hunk covering the whole file is used in the unexpected case where the line counts differ
+pub fn get_unified_diff(path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+
+    if old_lines.len() != new_lines.len() {
+        diff.push_str(&format!(
+            "@@ -1,{} +1,{} @@\n",
+            old_lines.len(),
+            new_lines.len()
+        ));
+        old_lines
+            .iter()
+            .for_each(|line| diff.push_str(&format!("-{line}\n")));
+        new_lines
+            .iter()
+            .for_each(|line| diff.push_str(&format!("+{line}\n")));
+        return diff;
+    }
+
+    let changed = (0..old_lines.len())
+        .filter(|i| old_lines[*i] != new_lines[*i])
+        .collect::<Vec<_>>();
+
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for i in changed.iter() {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + CONTEXT_LINES + 1).min(old_lines.len());
+
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let length = end - start;
+        diff.push_str(&format!(
+            "@@ -{},{length} +{},{length} @@\n",
+            start + 1,
+            start + 1
+        ));
+
+        for i in start..end {
+            if old_lines[i] == new_lines[i] {
+                diff.push_str(&format!(" {}\n", old_lines[i]));
+            } else {
+                diff.push_str(&format!("-{}\n+{}\n", old_lines[i], new_lines[i]));
+            }
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_unified_diff() {
+        let old = "[package]\nname = \"foo\"\n\n[dependencies]\nlog = \"0.4.0\"\nserde = \"1.0.0\"\n\n\n\n\n\n\n\ntokio = \"1.0.0\"\n";
+        let new = old.replace("1.0.0", "1.2.0");
+
+        assert_eq!(get_unified_diff("Cargo.toml", old, old), "");
+        assert_eq!(
+            get_unified_diff("crates/foo/Cargo.toml", old, &new),
+            [
+                "--- a/crates/foo/Cargo.toml",
+                "+++ b/crates/foo/Cargo.toml",
+                "@@ -3,7 +3,7 @@",
+                " ",
+                " [dependencies]",
+                " log = \"0.4.0\"",
+                "-serde = \"1.0.0\"",
+                "+serde = \"1.2.0\"",
+                " ",
+                " ",
+                " ",
+                "@@ -11,4 +11,4 @@",
+                " ",
+                " ",
+                " ",
+                "-tokio = \"1.0.0\"",
+                "+tokio = \"1.2.0\"",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            get_unified_diff("Cargo.toml", "a\n", "b\nc\n"),
+            "--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,1 +1,2 @@\n-a\n+b\n+c\n"
+        );
+    }
+}
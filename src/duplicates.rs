@@ -0,0 +1,299 @@
+use semver::Version;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use toml_edit::DocumentMut;
+
+struct LockedPackage {
+    name: String,
+    version: String,
+    local: bool,
+    dependencies: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Duplicate {
+    pub name: String,
+    // Every locked version, oldest first, along with the direct dependencies pulling it in
+    pub versions: Vec<(String, Vec<String>)>,
+}
+
+impl Duplicate {
+    // Bumping the direct dependencies pulling in an older version is the way to converge on the
+    // newest one, the others already use it
+    fn get_suggested_bumps(&self) -> Vec<&str> {
+        self.versions
+            .split_last()
+            .map(|(_, older)| {
+                older
+                    .iter()
+                    .flat_map(|(_, pulled_by)| pulled_by.iter().map(String::as_str))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn find_duplicates(lockfile: &str) -> Result<Vec<Duplicate>, String> {
+    let packages = parse_lockfile(lockfile)?;
+    let index = index_packages(&packages);
+
+    let mut versions = BTreeMap::<&str, BTreeMap<&str, BTreeSet<&str>>>::new();
+    for package in packages.iter().filter(|package| !package.local) {
+        versions
+            .entry(&package.name)
+            .or_default()
+            .insert(&package.version, BTreeSet::new());
+    }
+    versions.retain(|_, versions| versions.len() > 1);
+
+    for direct in packages
+        .iter()
+        .filter(|package| package.local)
+        .flat_map(|package| &package.dependencies)
+        .filter_map(|dependency| resolve_dependency(&packages, &index, dependency))
+        .filter(|i| !packages[*i].local)
+    {
+        for i in get_reachable_packages(&packages, &index, direct) {
+            let package = &packages[i];
+            if let Some(pulled_by) = versions
+                .get_mut(package.name.as_str())
+                .and_then(|versions| versions.get_mut(package.version.as_str()))
+            {
+                pulled_by.insert(&packages[direct].name);
+            }
+        }
+    }
+
+    Ok(versions
+        .into_iter()
+        .map(|(name, versions)| {
+            let mut versions = versions
+                .into_iter()
+                .map(|(version, pulled_by)| {
+                    (
+                        version.to_string(),
+                        pulled_by.into_iter().map(str::to_string).collect(),
+                    )
+                })
+                .collect::<Vec<(String, Vec<String>)>>();
+            versions.sort_by_key(|(version, _)| Version::parse(version).ok());
+
+            Duplicate {
+                name: name.to_string(),
+                versions,
+            }
+        })
+        .collect())
+}
+
+pub fn get_suggested_bumps(duplicates: &[Duplicate]) -> Vec<String> {
+    duplicates
+        .iter()
+        .flat_map(Duplicate::get_suggested_bumps)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn render(duplicates: &[Duplicate]) -> Vec<String> {
+    if duplicates.is_empty() {
+        return vec!["No crates are locked at multiple versions.".to_string()];
+    }
+
+    let mut lines = vec![];
+    for duplicate in duplicates {
+        lines.push(format!(
+            "{} is locked at {} versions:",
+            duplicate.name,
+            duplicate.versions.len()
+        ));
+        for (version, pulled_by) in &duplicate.versions {
+            lines.push(format!("  {version} pulled in by {}", pulled_by.join(", ")));
+        }
+    }
+
+    let bumps = get_suggested_bumps(duplicates);
+    if !bumps.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "Updating {} may converge them on their newest version.",
+            bumps.join(", ")
+        ));
+    }
+
+    lines
+}
+
+fn parse_lockfile(lockfile: &str) -> Result<Vec<LockedPackage>, String> {
+    let document = lockfile
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("Unable to parse Cargo.lock: {e}"))?;
+    let Some(packages) = document
+        .get("package")
+        .and_then(|packages| packages.as_array_of_tables())
+    else {
+        return Ok(vec![]);
+    };
+
+    Ok(packages
+        .iter()
+        .filter_map(|package| {
+            Some(LockedPackage {
+                name: package.get("name")?.as_str()?.to_string(),
+                version: package.get("version")?.as_str()?.to_string(),
+                local: !package.contains_key("source"),
+                dependencies: package
+                    .get("dependencies")
+                    .and_then(|dependencies| dependencies.as_array())
+                    .map(|dependencies| {
+                        dependencies
+                            .iter()
+                            .filter_map(|dependency| dependency.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+// The packages are looked up by name for every dependency of the lockfile, so they are indexed once
+fn index_packages(packages: &[LockedPackage]) -> HashMap<&str, Vec<usize>> {
+    let mut index = HashMap::<&str, Vec<usize>>::new();
+    for (i, package) in packages.iter().enumerate() {
+        index.entry(&package.name).or_default().push(i);
+    }
+    index
+}
+
+// Dependencies are only written as `<name> <version>` when the name is ambiguous, followed by the
+// source when the version is ambiguous as well
+fn resolve_dependency(
+    packages: &[LockedPackage],
+    index: &HashMap<&str, Vec<usize>>,
+    dependency: &str,
+) -> Option<usize> {
+    let mut parts = dependency.split(' ');
+    let name = parts.next()?;
+    let version = parts.next();
+
+    index
+        .get(name)?
+        .iter()
+        .copied()
+        .find(|i| version.is_none_or(|version| packages[*i].version == version))
+}
+
+// The packages depended upon by the direct dependency, itself included, without going through
+// the workspace members
+fn get_reachable_packages(
+    packages: &[LockedPackage],
+    index: &HashMap<&str, Vec<usize>>,
+    direct: usize,
+) -> BTreeSet<usize> {
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![direct];
+
+    while let Some(i) = stack.pop() {
+        if packages[i].local || !reachable.insert(i) {
+            continue;
+        }
+
+        stack.extend(
+            packages[i]
+                .dependencies
+                .iter()
+                .filter_map(|dependency| resolve_dependency(packages, index, dependency)),
+        );
+    }
+
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "clap",
+ "serde",
+ "syn 1.0.109",
+ "thiserror",
+]
+
+[[package]]
+name = "clap"
+version = "4.5.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 2.0.60",
+]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.60"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "thiserror"
+version = "1.0.60"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 2.0.60",
+]
+"#;
+
+    #[test]
+    fn test_find_duplicates() {
+        let duplicates = find_duplicates(LOCKFILE).unwrap();
+
+        assert_eq!(
+            duplicates,
+            vec![Duplicate {
+                name: "syn".to_string(),
+                versions: vec![
+                    (
+                        "1.0.109".to_string(),
+                        vec!["clap".to_string(), "syn".to_string()]
+                    ),
+                    (
+                        "2.0.60".to_string(),
+                        vec!["serde".to_string(), "thiserror".to_string()]
+                    ),
+                ],
+            }]
+        );
+        assert_eq!(get_suggested_bumps(&duplicates), vec!["clap", "syn"]);
+        assert_eq!(
+            render(&duplicates),
+            vec![
+                "syn is locked at 2 versions:",
+                "  1.0.109 pulled in by clap, syn",
+                "  2.0.60 pulled in by serde, thiserror",
+                "",
+                "Updating clap, syn may converge them on their newest version.",
+            ]
+        );
+
+        assert_eq!(find_duplicates("version = 3").unwrap(), vec![]);
+        assert!(find_duplicates("[[package").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::exit_code;
+
+/// Failures of the library functions, each with the exit code and hint the command line prints
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    ManifestRead {
+        path: String,
+        message: String,
+    },
+    ManifestParse {
+        path: String,
+        message: String,
+    },
+    UnknownPackage(String),
+    Network {
+        crate_name: String,
+        message: String,
+    },
+    Verification {
+        command: String,
+        status: String,
+    },
+    Hook {
+        hook: String,
+        command: String,
+        status: String,
+    },
+    Update(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Network { .. } => exit_code::NETWORK_FAILURE,
+            Error::Verification { .. } => exit_code::VERIFICATION_FAILED,
+            Error::Hook { .. } => exit_code::ABORTED,
+            Error::ManifestRead { .. }
+            | Error::ManifestParse { .. }
+            | Error::UnknownPackage(_)
+            | Error::Update(_) => exit_code::FAILURE,
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Error::ManifestRead { .. } => {
+                "run the command from a cargo package or workspace, next to its Cargo.toml"
+            }
+            Error::ManifestParse { .. } => "fix the syntax error, `cargo metadata` reports it too",
+            Error::UnknownPackage(_) => "use the `name` of a workspace member's [package] section",
+            Error::Network { .. } => {
+                "check your connection and the status of crates.io at https://status.crates.io"
+            }
+            Error::Verification { .. } => {
+                "use `--rollback` to restore the original manifests or `--no-check` to skip the verification"
+            }
+            Error::Hook { .. } => "the hook rejected the update, its output above explains why",
+            Error::Update(_) => {
+                "check that the manifests are writable, `rollback` restores the previous versions"
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ManifestRead { path, message } => write!(f, "Unable to read {path}: {message}"),
+            Error::ManifestParse { path, message } => {
+                write!(f, "Unable to parse {path}: {message}")
+            }
+            Error::UnknownPackage(package) => {
+                write!(f, "package `{package}` did not match any workspace member")
+            }
+            Error::Network {
+                crate_name,
+                message,
+            } => write!(f, "Unable to reach crates.io for {crate_name}: {message}"),
+            Error::Verification { command, status } => {
+                write!(f, "`{command}` failed with {status}")
+            }
+            Error::Hook {
+                hook,
+                command,
+                status,
+            } => write!(f, "The {hook} hook `{command}` failed with {status}"),
+            Error::Update(message) => write!(f, "Unable to apply the updates: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error() {
+        let error = Error::Network {
+            crate_name: "serde".to_string(),
+            message: "Couldn't resolve host name".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unable to reach crates.io for serde: Couldn't resolve host name"
+        );
+        assert_eq!(error.exit_code(), exit_code::NETWORK_FAILURE);
+
+        let error = Error::Verification {
+            command: "cargo check".to_string(),
+            status: "exit status: 101".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "`cargo check` failed with exit status: 101"
+        );
+        assert_eq!(error.exit_code(), exit_code::VERIFICATION_FAILED);
+        let error = Error::Update("Permission denied".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Unable to apply the updates: Permission denied"
+        );
+        assert_eq!(error.exit_code(), exit_code::FAILURE);
+    }
+}
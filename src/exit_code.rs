@@ -0,0 +1,35 @@
+use crate::error::Error;
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const OUTDATED_COMPATIBLE: i32 = 2;
+pub const OUTDATED_BREAKING: i32 = 3;
+pub const VULNERABLE: i32 = 4;
+pub const NOTHING_OUTDATED: i32 = 5;
+pub const ABORTED: i32 = 6;
+pub const NETWORK_FAILURE: i32 = 7;
+pub const VERIFICATION_FAILED: i32 = 8;
+
+pub fn get_exit_code(error: &(dyn std::error::Error + 'static)) -> i32 {
+    error
+        .downcast_ref::<Error>()
+        .map_or(FAILURE, |error| error.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_exit_code() {
+        let error: Box<dyn std::error::Error> = Error::Network {
+            crate_name: "serde".to_string(),
+            message: "timeout".to_string(),
+        }
+        .into();
+        assert_eq!(get_exit_code(error.as_ref()), NETWORK_FAILURE);
+
+        let error: Box<dyn std::error::Error> = "other".into();
+        assert_eq!(get_exit_code(error.as_ref()), FAILURE);
+    }
+}
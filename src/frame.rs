@@ -0,0 +1,222 @@
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::Print,
+    terminal::{Clear, ClearType},
+};
+use std::{fmt::Display, io::Write};
+use unicode_width::UnicodeWidthChar;
+
+// Lines of a screen built like the terminal would print them, with the dependency rows marked
+// so they can be scrolled to keep the cursor within the viewport
+#[derive(Default)]
+pub struct Frame {
+    lines: Vec<String>,
+    current: String,
+    list: Option<(usize, usize)>,
+    cursor: Option<usize>,
+}
+
+impl Frame {
+    pub fn print(&mut self, content: impl Display) {
+        self.current.push_str(&content.to_string());
+    }
+
+    pub fn next_line(&mut self, count: usize) {
+        self.lines.push(std::mem::take(&mut self.current));
+        self.lines
+            .extend(std::iter::repeat_n(String::new(), count.saturating_sub(1)));
+    }
+
+    pub fn start_list(&mut self) {
+        let start = self.lines.len();
+        self.list = Some((start, start));
+    }
+
+    pub fn end_list(&mut self) {
+        if let Some((_, end)) = self.list.as_mut() {
+            *end = self.lines.len();
+        }
+    }
+
+    pub fn mark_cursor(&mut self) {
+        self.cursor = Some(self.lines.len());
+    }
+
+    // Only the dependency rows are scrolled, the header and the footer are always shown
+    pub fn into_lines(mut self, width: usize, height: usize, scroll: &mut usize) -> Vec<String> {
+        if !self.current.is_empty() {
+            self.next_line(1);
+        }
+
+        let Some((start, end)) = self.list else {
+            return self.lines;
+        };
+        let others = self.lines[..start]
+            .iter()
+            .chain(&self.lines[end..])
+            .map(|line| get_line_height(line, width))
+            .sum::<usize>();
+        let window = height.saturating_sub(others).max(1);
+
+        if let Some(cursor) = self.cursor.map(|cursor| cursor - start) {
+            if cursor < *scroll {
+                *scroll = cursor;
+            } else if cursor >= *scroll + window {
+                *scroll = cursor + 1 - window;
+            }
+        }
+        *scroll = (*scroll).min((end - start).saturating_sub(window));
+
+        let footer = self.lines.split_off(end);
+        let list = self.lines.split_off(start);
+        self.lines
+            .into_iter()
+            .chain(list.into_iter().skip(*scroll).take(window))
+            .chain(footer)
+            .collect()
+    }
+}
+
+// The lines currently on the screen, so the next frame only rewrites the lines which changed
+#[derive(Default)]
+pub struct Screen {
+    previous: Vec<String>,
+    invalidated: bool,
+}
+
+impl Screen {
+    // Another view took over the terminal, or it was resized
+    pub fn invalidate(&mut self) {
+        self.previous.clear();
+        self.invalidated = true;
+    }
+
+    pub fn draw(
+        &mut self,
+        out: &mut impl Write,
+        lines: Vec<String>,
+        width: usize,
+    ) -> std::io::Result<()> {
+        if self.invalidated {
+            queue!(out, Clear(ClearType::All))?;
+            self.invalidated = false;
+        }
+
+        let mut row = 0;
+        let mut previous_row = 0;
+        let mut shifted = false;
+        for (i, line) in lines.iter().enumerate() {
+            let previous = self.previous.get(i);
+            // Once a line wraps differently, the following lines all move
+            shifted |= row != previous_row;
+
+            if shifted || previous != Some(line) {
+                queue!(
+                    out,
+                    MoveTo(0, row as u16),
+                    Print(line),
+                    Clear(ClearType::UntilNewLine)
+                )?;
+            }
+
+            row += get_line_height(line, width);
+            previous_row += previous.map_or(0, |previous| get_line_height(previous, width));
+        }
+
+        let previous_rows = self
+            .previous
+            .iter()
+            .map(|line| get_line_height(line, width))
+            .sum::<usize>();
+        // The cursor is left below the frame, where the output following the selection goes
+        queue!(out, MoveTo(0, row as u16))?;
+        if previous_rows > row || shifted {
+            queue!(out, Clear(ClearType::FromCursorDown))?;
+        }
+
+        self.previous = lines;
+        Ok(())
+    }
+}
+
+// Rows taken by a line once wrapped, its escape sequences taking no space and its wide
+// characters two columns
+fn get_line_height(line: &str, width: usize) -> usize {
+    let mut length = 0_usize;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            length += c.width().unwrap_or(0);
+        }
+    }
+
+    length.div_ceil(width.max(1)).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_frame(rows: usize, cursor: usize) -> Frame {
+        let mut frame = Frame::default();
+        frame.print("header");
+        frame.next_line(2);
+        frame.start_list();
+        for i in 0..rows {
+            if i == cursor {
+                frame.mark_cursor();
+            }
+            frame.print(format!("row {i}"));
+            frame.next_line(1);
+        }
+        frame.end_list();
+        frame.next_line(1);
+        frame.print("footer");
+        frame
+    }
+
+    #[test]
+    fn test_into_lines() {
+        let mut scroll = 0;
+        assert_eq!(
+            get_frame(3, 0).into_lines(80, 24, &mut scroll),
+            vec!["header", "", "row 0", "row 1", "row 2", "", "footer"]
+        );
+
+        assert_eq!(
+            get_frame(10, 7).into_lines(80, 7, &mut scroll),
+            vec!["header", "", "row 5", "row 6", "row 7", "", "footer"]
+        );
+        assert_eq!(scroll, 5);
+        assert_eq!(
+            get_frame(10, 6).into_lines(80, 7, &mut scroll),
+            vec!["header", "", "row 5", "row 6", "row 7", "", "footer"]
+        );
+        assert_eq!(
+            get_frame(10, 1).into_lines(80, 7, &mut scroll),
+            vec!["header", "", "row 1", "row 2", "row 3", "", "footer"]
+        );
+        assert_eq!(scroll, 1);
+    }
+
+    #[test]
+    fn test_draw() {
+        let lines = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect();
+        let mut screen = Screen::default();
+        let mut out = vec![];
+        screen.draw(&mut out, lines(&["a", "b", "c"]), 80).unwrap();
+
+        out.clear();
+        screen.draw(&mut out, lines(&["a", "x", "c"]), 80).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains('x'));
+        assert!(!out.contains('a') && !out.contains('c'));
+
+        assert_eq!(get_line_height("\x1b[32mabcd\x1b[0m", 2), 2);
+        assert_eq!(get_line_height("", 80), 1);
+        assert_eq!(get_line_height("日本語", 4), 2);
+    }
+}
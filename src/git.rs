@@ -0,0 +1,230 @@
+use std::process::Command;
+
+use crate::{
+    dependency::{Dependencies, Dependency, DependencyKind},
+    logger, policy, report, toolchain,
+};
+
+pub const DEFAULT_COMMIT_MESSAGE: &str = "chore(deps): bump {crate} from {old} to {new}";
+pub const DEFAULT_BATCH_COMMIT_MESSAGE: &str = "chore(deps): bump {count} dependencies";
+
+fn render_template(template: &str, dependency: &Dependency) -> String {
+    template
+        .replace("{crate}", &dependency.name)
+        .replace("{old}", &dependency.current_version)
+        .replace("{new}", &dependency.latest_version)
+        .replace("{kind}", dependency.kind.name())
+}
+
+pub fn get_commit_message(
+    dependencies: &Dependencies,
+    template: &str,
+    batch_template: &str,
+) -> String {
+    let bumps = dependencies
+        .iter()
+        .map(|dependency| render_template(template, dependency))
+        .collect::<Vec<_>>();
+
+    match bumps.as_slice() {
+        [bump] => bump.to_string(),
+        _ => format!(
+            "{}\n\n{}",
+            batch_template.replace("{count}", &bumps.len().to_string()),
+            bumps
+                .iter()
+                .map(|bump| format!("- {bump}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+pub fn append_changelog(message: String, changelog: &str) -> String {
+    if changelog.is_empty() {
+        message
+    } else {
+        format!("{message}\n\n{changelog}")
+    }
+}
+
+pub fn get_changed_files(dependencies: &Dependencies) -> Vec<String> {
+    let mut files = dependencies
+        .cargo_toml_files
+        .keys()
+        .map(|workspace_path| format!("{workspace_path}/Cargo.toml"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    if dependencies
+        .iter()
+        .any(|dependency| dependency.kind == DependencyKind::Toolchain)
+    {
+        files.push(format!("./{}", toolchain::TOOLCHAIN_FILE));
+    }
+    if std::path::Path::new("./Cargo.lock").exists() {
+        files.push("./Cargo.lock".to_string());
+    }
+
+    files
+}
+
+fn run_git(args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    logger::info(format!("Running git {}", args.join(" ")));
+
+    let status = Command::new("git").args(args).status()?;
+
+    if !status.success() {
+        return Err(format!("`git {}` failed with {status}", args[0]).into());
+    }
+
+    Ok(())
+}
+
+pub fn get_default_branch_name() -> String {
+    format!(
+        "deps/update-{}",
+        policy::get_date_from_days_since_epoch(policy::get_days_since_epoch_today())
+    )
+}
+
+fn branch_exists(name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{name}"))
+        .stdout(std::process::Stdio::null())
+        .status()?
+        .success())
+}
+
+pub fn get_user_name() -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()?;
+    let name = String::from_utf8(output.stdout).ok()?.trim().to_string();
+
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+pub fn switch_branch(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if branch_exists(name)? {
+        run_git(&["switch", "--quiet", name])
+    } else {
+        run_git(&["switch", "--quiet", "--create", name])
+    }
+}
+
+pub fn open_pull_request(
+    branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_git(&["push", "--quiet", "--set-upstream", "origin", branch])?;
+
+    logger::info(format!("Running gh pr create --head {branch}"));
+    let status = Command::new("gh")
+        .args([
+            "pr", "create", "--head", branch, "--title", title, "--body", body,
+        ])
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("`gh pr create` failed with {status}").into());
+    }
+
+    Ok(())
+}
+
+pub fn get_pull_request_body(dependencies: &Dependencies) -> String {
+    format!(
+        "Updates the following dependencies:\n\n{}",
+        report::render_markdown_table(dependencies)
+    )
+}
+
+pub fn commit(files: &[String], message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut add_args = vec!["add", "--"];
+    add_args.extend(files.iter().map(|file| file.as_str()));
+
+    run_git(&add_args)?;
+    run_git(&["commit", "--quiet", "-m", message])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::DependencyKind;
+    use std::collections::HashMap;
+
+    fn dependency(name: &str, current_version: &str, latest_version: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            current_version: current_version.to_string(),
+            latest_version: latest_version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_commit_message() {
+        let dependencies =
+            Dependencies::new(vec![dependency("serde", "1.0.1", "1.0.2")], HashMap::new());
+        assert_eq!(
+            get_commit_message(
+                &dependencies,
+                DEFAULT_COMMIT_MESSAGE,
+                DEFAULT_BATCH_COMMIT_MESSAGE
+            ),
+            "chore(deps): bump serde from 1.0.1 to 1.0.2"
+        );
+
+        let dependencies = Dependencies::new(
+            vec![
+                dependency("serde", "1.0.1", "1.0.2"),
+                Dependency {
+                    kind: DependencyKind::Dev,
+                    ..dependency("tokio", "1.0.0", "1.1.0")
+                },
+            ],
+            HashMap::new(),
+        );
+        assert_eq!(
+            get_commit_message(
+                &dependencies,
+                DEFAULT_COMMIT_MESSAGE,
+                DEFAULT_BATCH_COMMIT_MESSAGE
+            ),
+            "chore(deps): bump 2 dependencies\n\n- chore(deps): bump serde from 1.0.1 to 1.0.2\n- chore(deps): bump tokio from 1.0.0 to 1.1.0"
+        );
+        assert_eq!(
+            get_commit_message(
+                &dependencies,
+                "Update {crate} ({kind}) to {new}",
+                "Update dependencies"
+            ),
+            "Update dependencies\n\n- Update serde (normal) to 1.0.2\n- Update tokio (dev) to 1.1.0"
+        );
+    }
+
+    #[test]
+    fn test_append_changelog() {
+        assert_eq!(append_changelog("Title".to_string(), ""), "Title");
+        assert_eq!(
+            append_changelog("Title".to_string(), "serde 1.0.1 -> 1.0.2"),
+            "Title\n\nserde 1.0.1 -> 1.0.2"
+        );
+    }
+
+    #[test]
+    fn test_get_pull_request_body() {
+        let dependencies =
+            Dependencies::new(vec![dependency("serde", "1.0.1", "1.0.2")], HashMap::new());
+        let body = get_pull_request_body(&dependencies);
+
+        assert!(body.starts_with("Updates the following dependencies:\n\n| Crate |"));
+        assert!(body.ends_with(
+            "| [serde](https://crates.io/crates/serde) | normal | - | 1.0.1 | 1.0.2 | - |  |  |"
+        ));
+    }
+}
@@ -0,0 +1,331 @@
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use toml_edit::DocumentMut;
+
+use crate::{
+    args::Args,
+    dependency::{Dependencies, Dependency, DependencyKind},
+    git, logger, policy,
+};
+
+pub const HISTORY_FILE: &str = ".cargo-interactive-update/history.json";
+
+pub fn record(dependencies: &Dependencies) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Path::new(HISTORY_FILE);
+    let mut sessions = read_sessions(path)?;
+
+    sessions.push(get_session(
+        dependencies,
+        &get_current_timestamp(),
+        &get_user(),
+    ));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    logger::info(format!("Recording the updates in {HISTORY_FILE}"));
+    std::fs::write(path, serde_json::to_string_pretty(&sessions)? + "\n")?;
+
+    Ok(())
+}
+
+pub fn print_history() -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = read_sessions(Path::new(HISTORY_FILE))?;
+
+    if sessions.is_empty() {
+        logger::print("No updates have been recorded yet.\n");
+    } else {
+        print!("{}", render(&sessions));
+    }
+
+    Ok(())
+}
+
+pub fn rollback(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Path::new(HISTORY_FILE);
+    let mut sessions = read_sessions(path)?;
+
+    let Some(session) = sessions.pop() else {
+        logger::print("No updates have been recorded yet.\n");
+        return Ok(());
+    };
+
+    let mut cargo_toml_files = HashMap::new();
+    let mut dependencies = vec![];
+    for dependency in get_session_dependencies(&session) {
+        let workspace_path = dependency
+            .workspace_path
+            .clone()
+            .unwrap_or_else(|| ".".to_string());
+        if !cargo_toml_files.contains_key(&workspace_path) {
+            let content = std::fs::read_to_string(dependency.manifest_path())?;
+            cargo_toml_files.insert(workspace_path.clone(), content.parse::<DocumentMut>()?);
+        }
+
+        if is_declared(&cargo_toml_files[&workspace_path], &dependency) {
+            dependencies.push(dependency);
+        } else {
+            logger::warn(format!(
+                "`{}` is no longer declared in {}, skipping it",
+                dependency.name,
+                dependency.manifest_path()
+            ));
+        }
+    }
+
+    Dependencies::new(dependencies, cargo_toml_files).restore_versions(args)?;
+
+    logger::info(format!("Removing the last session from {HISTORY_FILE}"));
+    std::fs::write(path, serde_json::to_string_pretty(&sessions)? + "\n")?;
+    logger::print(format!(
+        "Rolled back the updates applied on {} by {}.\n",
+        session["timestamp"].as_str().unwrap_or_default(),
+        session["user"].as_str().unwrap_or_default(),
+    ));
+
+    Ok(())
+}
+
+pub fn read_sessions(path: &Path) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Unable to parse {}: {e}", path.display()).into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Who ran the update, as known by git and otherwise by the operating system
+fn get_user() -> String {
+    git::get_user_name()
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub fn get_current_timestamp() -> String {
+    get_timestamp(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs()),
+    )
+}
+
+fn get_timestamp(seconds: u64) -> String {
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        policy::get_date_from_days_since_epoch((seconds / 86_400) as i64),
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+fn get_session(dependencies: &Dependencies, timestamp: &str, user: &str) -> Value {
+    let updates = dependencies
+        .iter()
+        .map(|dependency| {
+            json!({
+                "name": dependency.name,
+                "package": dependency.package,
+                "kind": dependency.kind.name(),
+                "manifest_path": dependency.manifest_path(),
+                "old_version": dependency.current_version,
+                "new_version": dependency.requirement.format(&dependency.latest_version),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "timestamp": timestamp,
+        "user": user,
+        "updates": updates,
+    })
+}
+
+// Dependencies going back from the new versions of a session to the old ones
+fn get_session_dependencies(session: &Value) -> Vec<Dependency> {
+    session["updates"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|update| {
+            let kind = DependencyKind::ordered()
+                .into_iter()
+                .find(|kind| Some(kind.name()) == update["kind"].as_str())?;
+            let workspace_path = match kind {
+                DependencyKind::Toolchain => "",
+                _ => update["manifest_path"]
+                    .as_str()?
+                    .strip_prefix("./")?
+                    .strip_suffix("Cargo.toml")?
+                    .trim_end_matches('/'),
+            };
+
+            Some(Dependency {
+                name: update["name"].as_str()?.to_string(),
+                package: update["package"].as_str().map(str::to_string),
+                current_version: update["new_version"].as_str()?.to_string(),
+                latest_version: update["old_version"].as_str()?.to_string(),
+                kind,
+                workspace_path: (!workspace_path.is_empty()).then(|| workspace_path.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn is_declared(cargo_toml: &DocumentMut, dependency: &Dependency) -> bool {
+    let section = match dependency.kind {
+        DependencyKind::Toolchain => return true,
+        DependencyKind::Workspace => cargo_toml
+            .get("workspace")
+            .and_then(|workspace| workspace.get("dependencies")),
+        kind => cargo_toml.get(kind.section()),
+    };
+
+    section
+        .and_then(|section| section.get(&dependency.name))
+        .is_some()
+}
+
+fn render(sessions: &[Value]) -> String {
+    sessions
+        .iter()
+        .map(|session| {
+            let updates = session["updates"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|update| {
+                    format!(
+                        "  {} {} -> {} ({}, {})\n",
+                        update["name"].as_str().unwrap_or_default(),
+                        update["old_version"].as_str().unwrap_or_default(),
+                        update["new_version"].as_str().unwrap_or_default(),
+                        update["kind"].as_str().unwrap_or_default(),
+                        update["manifest_path"].as_str().unwrap_or_default(),
+                    )
+                })
+                .collect::<String>();
+
+            format!(
+                "{} by {}\n{updates}",
+                session["timestamp"].as_str().unwrap_or_default(),
+                session["user"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::{Dependency, DependencyKind};
+
+    #[test]
+    fn test_get_timestamp() {
+        assert_eq!(get_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(get_timestamp(1_760_617_825), "2025-10-16T12:30:25Z");
+    }
+
+    #[test]
+    fn test_get_session() {
+        let dependencies = Dependencies::new(
+            vec![Dependency {
+                name: "serde".to_string(),
+                current_version: "1.0.0".to_string(),
+                latest_version: "1.0.1".to_string(),
+                kind: DependencyKind::Dev,
+                workspace_path: Some("crates/foo".to_string()),
+                ..Default::default()
+            }],
+            Default::default(),
+        );
+
+        let session = get_session(&dependencies, "2025-10-16T12:30:25Z", "alice");
+        assert_eq!(
+            session,
+            json!({
+                "timestamp": "2025-10-16T12:30:25Z",
+                "user": "alice",
+                "updates": [{
+                    "name": "serde",
+                    "package": null,
+                    "kind": "dev",
+                    "manifest_path": "./crates/foo/Cargo.toml",
+                    "old_version": "1.0.0",
+                    "new_version": "1.0.1",
+                }],
+            })
+        );
+
+        assert_eq!(
+            render(&[session.clone(), session]),
+            "2025-10-16T12:30:25Z by alice\n  serde 1.0.0 -> 1.0.1 (dev, ./crates/foo/Cargo.toml)\n\n2025-10-16T12:30:25Z by alice\n  serde 1.0.0 -> 1.0.1 (dev, ./crates/foo/Cargo.toml)\n"
+        );
+    }
+
+    #[test]
+    fn test_get_session_dependencies() {
+        let session = json!({
+            "updates": [
+                {
+                    "name": "serde",
+                    "package": null,
+                    "kind": "dev",
+                    "manifest_path": "./crates/foo/Cargo.toml",
+                    "old_version": "1.0.0",
+                    "new_version": "=1.0.1",
+                },
+                {
+                    "name": "json",
+                    "package": "serde_json",
+                    "kind": "workspace",
+                    "manifest_path": "./Cargo.toml",
+                    "old_version": "1.0.0",
+                    "new_version": "1.1.0",
+                },
+                { "name": "tokio", "kind": "unknown" },
+            ],
+        });
+
+        let dependencies = get_session_dependencies(&session);
+        assert_eq!(dependencies.len(), 2);
+        assert_eq!(dependencies[0].name, "serde");
+        assert_eq!(dependencies[0].latest_version, "1.0.0");
+        assert_eq!(dependencies[0].kind, DependencyKind::Dev);
+        assert_eq!(dependencies[0].manifest_path(), "./crates/foo/Cargo.toml");
+        assert_eq!(dependencies[1].crate_name(), "serde_json");
+        assert_eq!(dependencies[1].workspace_path, None);
+
+        let cargo_toml =
+            "[workspace.dependencies]\njson = { package = \"serde_json\", version = \"1.1.0\" }"
+                .parse::<DocumentMut>()
+                .unwrap();
+        assert!(is_declared(&cargo_toml, &dependencies[1]));
+        assert!(!is_declared(&cargo_toml, &dependencies[0]));
+    }
+
+    #[test]
+    fn test_read_sessions() {
+        let dir = std::env::temp_dir().join(format!("history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.json");
+
+        assert!(read_sessions(&path).unwrap().is_empty());
+
+        std::fs::write(&path, "[{\"timestamp\": \"2025-10-16T12:30:25Z\"}]").unwrap();
+        assert_eq!(read_sessions(&path).unwrap().len(), 1);
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(read_sessions(&path).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+use std::{collections::BTreeSet, sync::Arc};
+use toml_edit::{DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::dependency::{set_version, Dependencies, DependencyKind};
+
+const MEMBER_KINDS: [DependencyKind; 3] = [
+    DependencyKind::Normal,
+    DependencyKind::Dev,
+    DependencyKind::Build,
+];
+
+// Keys which can't be set along `workspace = true`, they are moved to [workspace.dependencies]
+const WORKSPACE_KEYS: [&str; 3] = ["version", "package", "registry"];
+
+// Moves the selected dependencies declared by several members into the root's
+// [workspace.dependencies], the members inheriting them instead. Returns the hoisted crates.
+pub fn hoist(dependencies: &mut Dependencies) -> Result<Vec<String>, String> {
+    if dependencies
+        .cargo_toml_files
+        .get(".")
+        .is_none_or(|root| !root.contains_key("workspace"))
+    {
+        return Err("Hoisting requires a workspace, the root Cargo.toml has no [workspace]".into());
+    }
+
+    let names = dependencies
+        .iter()
+        .filter(|dependency| MEMBER_KINDS.contains(&dependency.kind))
+        .map(|dependency| {
+            (
+                dependency.name.clone(),
+                dependency
+                    .workspace_path
+                    .clone()
+                    .unwrap_or_else(|| ".".to_string()),
+                dependency.kind,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut hoisted = BTreeSet::new();
+    for (name, workspace_path, kind) in names {
+        if hoisted.contains(&name) {
+            continue;
+        }
+
+        let mut declarations = get_declarations(dependencies, &name);
+        let members = declarations
+            .iter()
+            .map(|(workspace_path, _)| workspace_path)
+            .collect::<BTreeSet<_>>();
+        if members.len() < 2 {
+            continue;
+        }
+
+        // The updated declaration of the selected dependency defines the inherited version
+        let Some(entry) = dependencies.cargo_toml_files[&workspace_path]
+            .get(kind.section())
+            .and_then(|section| section.get(&name))
+            .and_then(get_workspace_entry)
+        else {
+            continue;
+        };
+        set_workspace_entry(
+            Arc::make_mut(dependencies.cargo_toml_files.get_mut(".").unwrap()),
+            &name,
+            entry,
+        );
+
+        for (workspace_path, kind) in declarations.drain(..) {
+            let section = dependencies
+                .cargo_toml_files
+                .get_mut(&workspace_path)
+                .and_then(|cargo_toml| Arc::make_mut(cargo_toml).get_mut(kind.section()));
+            if let Some(item) = section.and_then(|section| section.get_mut(&name)) {
+                inherit(item);
+            }
+        }
+        hoisted.insert(name);
+    }
+
+    Ok(hoisted.into_iter().collect())
+}
+
+// The declarations of the crate which don't already inherit it from the workspace
+fn get_declarations(dependencies: &Dependencies, name: &str) -> Vec<(String, DependencyKind)> {
+    let mut declarations = vec![];
+
+    for (workspace_path, cargo_toml) in dependencies.cargo_toml_files.iter() {
+        for kind in MEMBER_KINDS {
+            let Some(item) = cargo_toml
+                .get(kind.section())
+                .and_then(|section| section.get(name))
+            else {
+                continue;
+            };
+
+            let is_inherited = item
+                .get("workspace")
+                .and_then(|workspace| workspace.as_bool())
+                .unwrap_or(false);
+            let is_local = item.get("path").is_some() || item.get("git").is_some();
+            if !is_inherited && !is_local {
+                declarations.push((workspace_path.clone(), kind));
+            }
+        }
+    }
+
+    declarations.sort_by(|a, b| a.0.cmp(&b.0));
+    declarations
+}
+
+fn get_workspace_entry(item: &Item) -> Option<Value> {
+    if let Some(version) = item.as_str() {
+        return Some(Value::from(version));
+    }
+
+    let table = item.as_table_like()?;
+    let mut entry = InlineTable::new();
+    for key in WORKSPACE_KEYS {
+        if let Some(value) = table.get(key).and_then(Item::as_value) {
+            let mut value = value.clone();
+            value.decor_mut().clear();
+            entry.insert(key, value);
+        }
+    }
+
+    match entry.get("version") {
+        Some(version) if entry.len() == 1 => Some(Value::from(version.as_str()?)),
+        _ => Some(Value::InlineTable(entry)),
+    }
+}
+
+// An existing entry keeps its other keys, such as its features
+fn set_workspace_entry(root: &mut DocumentMut, name: &str, entry: Value) {
+    let workspace = root["workspace"].as_table_mut().unwrap();
+    if !workspace.contains_key("dependencies") {
+        workspace.insert("dependencies", Item::Table(Table::new()));
+    }
+    let section = workspace["dependencies"].as_table_like_mut().unwrap();
+
+    match (section.get_mut(name), entry) {
+        (Some(Item::Value(current @ Value::String(_))), entry) => {
+            let decor = current.decor().clone();
+            *current = entry;
+            *current.decor_mut() = decor;
+        }
+        (Some(current), Value::String(version)) if current.is_table_like() => {
+            set_version(&mut current["version"], version.value());
+        }
+        (Some(current), Value::InlineTable(entry)) if current.is_table_like() => {
+            for (key, value) in entry.iter() {
+                set_version(&mut current[key], value.as_str().unwrap_or_default());
+            }
+        }
+        (_, entry) => {
+            section.insert(name, Item::Value(entry));
+        }
+    }
+}
+
+// The declaration becomes `{ workspace = true }`, keeping the keys a member can still set
+fn inherit(item: &mut Item) {
+    if let Some(table) = item.as_table_mut() {
+        for key in WORKSPACE_KEYS {
+            table.remove(key);
+        }
+        table.insert("workspace", toml_edit::value(true));
+        return;
+    }
+
+    let Some(current) = item.as_value_mut() else {
+        return;
+    };
+
+    let mut inherited = InlineTable::new();
+    inherited.insert("workspace", Value::from(true));
+    if let Some(table) = current.as_inline_table() {
+        for (key, value) in table.iter() {
+            if !WORKSPACE_KEYS.contains(&key) {
+                inherited.insert(key, value.clone());
+            }
+        }
+    }
+
+    let decor = current.decor().clone();
+    *current = Value::InlineTable(inherited);
+    *current.decor_mut() = decor;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::Dependency;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hoist() {
+        let cargo_toml_files = HashMap::from([
+            (
+                ".".to_string(),
+                "[workspace]\nmembers = [\"crates/*\"]\n".parse().unwrap(),
+            ),
+            (
+                "crates/a".to_string(),
+                "[dependencies]\nserde = { version = \"1.0.200\", features = [\"derive\"] } # serialization\ntokio = \"1.0.0\"\n"
+                    .parse()
+                    .unwrap(),
+            ),
+            (
+                "crates/b".to_string(),
+                "[dev-dependencies]\nserde = \"1.0.100\"\n".parse().unwrap(),
+            ),
+        ]);
+        let dependency = |name: &str| Dependency {
+            name: name.to_string(),
+            workspace_path: Some("crates/a".to_string()),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![dependency("serde"), dependency("tokio")],
+            cargo_toml_files,
+        );
+
+        assert_eq!(hoist(&mut dependencies), Ok(vec!["serde".to_string()]));
+        assert_eq!(
+            dependencies.cargo_toml_files["."].to_string(),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nserde = \"1.0.200\"\n"
+        );
+        assert_eq!(
+            dependencies.cargo_toml_files["crates/a"].to_string(),
+            "[dependencies]\nserde = { workspace = true, features = [\"derive\"] } # serialization\ntokio = \"1.0.0\"\n"
+        );
+        assert_eq!(
+            dependencies.cargo_toml_files["crates/b"].to_string(),
+            "[dev-dependencies]\nserde = { workspace = true }\n"
+        );
+
+        dependencies.cargo_toml_files.remove(".");
+        assert!(hoist(&mut dependencies).is_err());
+    }
+
+    #[test]
+    fn test_set_workspace_entry() {
+        let mut root = "[workspace.dependencies]\nserde = { version = \"1.0.0\", features = [\"derive\"] }\nlog = \"0.4.0\" # logging\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        set_workspace_entry(&mut root, "serde", Value::from("1.0.200"));
+        set_workspace_entry(&mut root, "log", Value::from("0.4.22"));
+        assert_eq!(
+            root.to_string(),
+            "[workspace.dependencies]\nserde = { version = \"1.0.200\", features = [\"derive\"] }\nlog = \"0.4.22\" # logging\n"
+        );
+    }
+}
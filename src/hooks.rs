@@ -0,0 +1,181 @@
+use serde_json::{json, Value};
+use std::io::Write;
+use toml_edit::Item;
+
+use crate::{
+    cargo::CargoDependencies,
+    dependency::{self, Dependencies},
+    error::Error,
+    logger, report,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Hook {
+    PreFetch,
+    PostSelect,
+    PreWrite,
+    PostVerify,
+}
+
+impl Hook {
+    const ALL: [Hook; 4] = [
+        Hook::PreFetch,
+        Hook::PostSelect,
+        Hook::PreWrite,
+        Hook::PostVerify,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreFetch => "pre-fetch",
+            Hook::PostSelect => "post-select",
+            Hook::PreWrite => "pre-write",
+            Hook::PostVerify => "post-verify",
+        }
+    }
+}
+
+// External commands run at each hook point, in the order of the configuration files
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hooks(Vec<(Hook, String)>);
+
+impl Hooks {
+    pub fn extend_from_item(&mut self, item: &Item, source: &str) -> Result<(), String> {
+        let table = item
+            .as_table_like()
+            .ok_or_else(|| format!("Hooks in {source} must be a table"))?;
+
+        for (key, value) in table.iter() {
+            let hook = Hook::ALL
+                .into_iter()
+                .find(|hook| hook.name() == key)
+                .ok_or_else(|| format!("Unknown hook `{key}` in {source}"))?;
+            let invalid = || format!("Invalid value for the `{key}` hook in {source}");
+
+            let commands = match value.as_str() {
+                Some(command) => vec![command.to_string()],
+                None => value
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|command| command.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid)?,
+            };
+            self.0
+                .extend(commands.into_iter().map(|command| (hook, command)));
+        }
+
+        Ok(())
+    }
+
+    // The payload is written as JSON to the standard input of each command, a failing command
+    // aborts the update
+    pub fn run(&self, hook: Hook, mut payload: Value) -> Result<(), Box<dyn std::error::Error>> {
+        payload["hook"] = json!(hook.name());
+
+        for (_, command_line) in self.0.iter().filter(|(h, _)| *h == hook) {
+            logger::info(format!("Running the {} hook {command_line}", hook.name()));
+
+            let mut child = dependency::get_shell_command(command_line)
+                .stdin(std::process::Stdio::piped())
+                .spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                // Commands are free to ignore the payload and exit before it is written
+                let _ = stdin.write_all(payload.to_string().as_bytes());
+            }
+            let status = child.wait()?;
+
+            if !status.success() {
+                return Err(Error::Hook {
+                    hook: hook.name().to_string(),
+                    command: command_line.to_string(),
+                    status: status.to_string(),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn get_payload(dependencies: &Dependencies) -> Value {
+    json!({
+        "dependencies": dependencies
+            .iter()
+            .map(report::get_dependency_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+// Only the declared versions are known before fetching the latest ones
+pub fn get_pre_fetch_payload(dependencies: &CargoDependencies) -> Value {
+    json!({
+        "dependencies": dependencies
+            .get_declared_dependencies()
+            .into_iter()
+            .map(|(workspace_path, dependency)| json!({
+                "name": dependency.name,
+                "package": dependency.package,
+                "kind": dependency.kind.name(),
+                "manifest_path": match workspace_path {
+                    Some(workspace_path) => format!("./{workspace_path}/Cargo.toml"),
+                    None => "./Cargo.toml".to_string(),
+                },
+                "version": dependency.version,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_hooks(content: &str) -> Result<Hooks, String> {
+        let document = content.parse::<toml_edit::DocumentMut>().unwrap();
+        let mut hooks = Hooks::default();
+        hooks.extend_from_item(&document["hooks"], "config.toml")?;
+        Ok(hooks)
+    }
+
+    #[test]
+    fn test_extend_from_item() {
+        assert_eq!(
+            get_hooks("[hooks]\npre-fetch = \"./audit.sh\"\npost-select = [\"a\", \"b\"]"),
+            Ok(Hooks(vec![
+                (Hook::PreFetch, "./audit.sh".to_string()),
+                (Hook::PostSelect, "a".to_string()),
+                (Hook::PostSelect, "b".to_string()),
+            ]))
+        );
+        assert_eq!(
+            get_hooks("[hooks]\npre-commit = \"a\""),
+            Err("Unknown hook `pre-commit` in config.toml".to_string())
+        );
+        assert_eq!(
+            get_hooks("[hooks]\npre-write = [1]"),
+            Err("Invalid value for the `pre-write` hook in config.toml".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run() {
+        let hooks = get_hooks(
+            "[hooks]\npre-write = \"grep -q '\\\"hook\\\":\\\"pre-write\\\"'\"\npost-verify = \"exit 3\"",
+        )
+        .unwrap();
+
+        assert!(hooks.run(Hook::PreWrite, json!({})).is_ok());
+        assert!(hooks.run(Hook::PostSelect, json!({})).is_ok());
+        assert_eq!(
+            hooks
+                .run(Hook::PostVerify, json!({}))
+                .unwrap_err()
+                .to_string(),
+            "The post-verify hook `exit 3` failed with exit status: 3"
+        );
+    }
+}
@@ -0,0 +1,491 @@
+mod api;
+mod args;
+mod cache;
+mod cargo;
+mod changelog;
+mod cli;
+mod config;
+mod dependency;
+mod diff;
+mod duplicates;
+mod error;
+mod exit_code;
+mod frame;
+mod git;
+mod history;
+mod hoist;
+mod hooks;
+mod logger;
+mod policy;
+mod pool;
+mod report;
+mod rules;
+mod security;
+mod select;
+mod semver_checks;
+mod theme;
+mod toolchain;
+mod tree;
+mod unused;
+
+pub use api::{Advisory, RegistryVersion};
+pub use cargo::CargoDependencies;
+pub use dependency::{Dependencies, Dependency, DependencyKind, Requirement};
+pub use error::Error;
+
+/// How [`apply_updates`] writes and verifies the updates, defaulting to what the command line
+/// does when confirming with `--yes`
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct UpdateOptions {
+    /// Run `cargo check` after updating the manifests
+    pub verify: bool,
+    /// Update `Cargo.lock` after updating the manifests
+    pub update_lockfile: bool,
+    /// Record the update in the history used by `cargo interactive-update rollback`
+    pub record_history: bool,
+    /// Commit the updated manifests and lockfile with git
+    pub commit: bool,
+    /// Don't print the progress of the update
+    pub quiet: bool,
+}
+
+impl Default for UpdateOptions {
+    fn default() -> Self {
+        Self {
+            verify: true,
+            update_lockfile: true,
+            record_history: true,
+            commit: false,
+            quiet: false,
+        }
+    }
+}
+
+impl From<UpdateOptions> for args::Args {
+    fn from(options: UpdateOptions) -> Self {
+        Self {
+            yes: true,
+            no_check: !options.verify,
+            no_lockfile: !options.update_lockfile,
+            no_history: !options.record_history,
+            commit: options.commit,
+            quiet: options.quiet,
+            ..Default::default()
+        }
+    }
+}
+
+/// Reads the manifests of the package or workspace in the current directory, which is also
+/// where [`apply_updates`] writes them and runs the verification, like the command line does
+pub fn gather_dependencies() -> Result<CargoDependencies, Error> {
+    CargoDependencies::gather_dependencies(".")
+}
+
+/// Looks up the latest versions on crates.io, returning the dependencies that are outdated
+pub fn retrieve_outdated(
+    dependencies: CargoDependencies,
+    include_prereleases: bool,
+) -> Result<Dependencies, Error> {
+    Ok(dependencies
+        .retrieve_dependencies(None, cargo::Retrieval::default())?
+        .outdated(include_prereleases))
+}
+
+/// Writes the latest versions of the dependencies to their manifests
+pub fn apply_updates(mut dependencies: Dependencies, options: UpdateOptions) -> Result<(), Error> {
+    logger::set_quiet(options.quiet);
+    let result = dependencies.apply_versions(options.into());
+    logger::print_warnings();
+    result.map_err(|error| match error.downcast::<Error>() {
+        Ok(error) => *error,
+        Err(error) => Error::Update(error.to_string()),
+    })
+}
+
+// Entry point of the binary, returning the exit code
+pub fn run_cli() -> i32 {
+    let result = run();
+    logger::print_warnings();
+
+    match result {
+        Ok(exit_code) => exit_code,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            if let Some(error) = error.downcast_ref::<error::Error>() {
+                eprintln!("Hint: {}", error.hint());
+            }
+            exit_code::get_exit_code(error.as_ref())
+        }
+    }
+}
+
+fn run() -> Result<i32, Box<dyn std::error::Error>> {
+    let mut args = config::parse_args()?;
+    logger::set_quiet(args.quiet);
+    logger::set_verbosity(args.verbose);
+    args.color.apply();
+
+    if args.generate_man {
+        std::io::Write::write_all(&mut std::io::stdout(), &args::get_man_page()?)?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if let Some(dir) = &args.recursive {
+        return check_projects(&args, dir);
+    }
+
+    let current_dir = std::env::current_dir()?;
+    let mut current_member = None;
+    if let Some((root, member)) = cargo::find_workspace_root(&current_dir) {
+        logger::info(format!("Using the workspace at {}", root.display()));
+        args.resolve_paths(&current_dir);
+        std::env::set_current_dir(root)?;
+        current_member = Some(member);
+    }
+
+    match &args.command {
+        Some(args::Command::History) => {
+            history::print_history()?;
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(args::Command::Rollback) => {
+            history::rollback(&args)?;
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(args::Command::Why { name }) => {
+            for line in tree::get_reverse_tree_lines(name, &args.cargo_flags())? {
+                println!("{line}");
+            }
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(args::Command::Info { name }) => {
+            println!("{}", get_info(name, current_member.as_deref())?);
+            return Ok(exit_code::SUCCESS);
+        }
+        Some(args::Command::Duplicates) | None => {}
+    }
+
+    if args.command == Some(args::Command::Duplicates) {
+        let lockfile = std::fs::read_to_string("./Cargo.lock")
+            .map_err(|e| format!("Unable to read Cargo.lock: {e}"))?;
+        let duplicates = duplicates::find_duplicates(&lockfile)?;
+        for line in duplicates::render(&duplicates) {
+            println!("{line}");
+        }
+
+        // The bumps are restricted to the `--only` dependencies when given
+        let bumps = duplicates::get_suggested_bumps(&duplicates)
+            .into_iter()
+            .filter(|name| args.only.is_empty() || args.only.contains(name))
+            .collect::<Vec<_>>();
+        if bumps.is_empty() {
+            return Ok(exit_code::SUCCESS);
+        }
+        println!();
+        args.only = bumps;
+    }
+
+    if let Some(interval) = args.watch {
+        watch(&args, current_member.as_deref(), interval);
+    }
+
+    let (mut dependencies, total_deps) = get_dependencies(&args, current_member.as_deref())?;
+
+    if args.semver_checks {
+        semver_checks::annotate(&mut dependencies);
+    }
+
+    if args.unused {
+        unused::annotate(&mut dependencies);
+    }
+
+    for misaligned in dependencies.get_misaligned_crates() {
+        let versions = misaligned
+            .versions
+            .iter()
+            .map(|(version, manifests)| format!("{version} in {}", manifests.join(", ")))
+            .collect::<Vec<_>>();
+        logger::warn(format!(
+            "`{}` is declared as {}, declaring it once in [workspace.dependencies] would keep it aligned",
+            misaligned.name,
+            versions.join(" and ")
+        ));
+    }
+
+    if args.security {
+        security::annotate(&mut dependencies)?;
+        args.sort = dependency::SortOrder::Severity;
+    }
+
+    if let Some(requirement) = args.pin {
+        dependencies.set_requirement(requirement);
+    }
+
+    if let Some((name, version)) = args.precise.clone() {
+        dependencies
+            .set_precise_version(&name, &version)?
+            .apply_versions(args)?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if let Some(format) = args.report_format() {
+        let outdated_deps = get_outdated_dependencies(&dependencies, &args);
+        let report = report::render(format, &dependencies, &outdated_deps, total_deps);
+
+        match &args.output {
+            Some(output) => {
+                std::fs::write(output, format!("{report}\n"))?;
+                logger::print(report::render_check_summary(&outdated_deps, total_deps));
+            }
+            None => println!("{report}"),
+        }
+
+        return Ok(args.fail_on.map_or(exit_code::SUCCESS, |fail_on| {
+            fail_on.get_exit_code(&outdated_deps)
+        }));
+    }
+
+    if args.check {
+        let outdated_deps = get_outdated_dependencies(&dependencies, &args);
+        let summary = report::render_check_summary(&outdated_deps, total_deps);
+        if let Some(output) = &args.output {
+            std::fs::write(output, &summary)?;
+        }
+        logger::print(summary);
+        return Ok(args
+            .fail_on
+            .unwrap_or(report::FailOn::Outdated)
+            .get_exit_code(&outdated_deps));
+    }
+
+    let total_outdated_deps = get_outdated_dependencies(&dependencies, &args).len();
+
+    if args.diff && (args.all || args.yes) {
+        let state = cli::State::new(dependencies, total_deps, &args);
+        print!(
+            "{}",
+            state.selected_dependencies().get_manifests_diff(args.hoist)
+        );
+        return Ok(exit_code::SUCCESS);
+    }
+
+    if total_outdated_deps == 0 {
+        logger::print(format!(
+            "All {total_deps} direct dependencies are up to date!\n"
+        ));
+        return Ok(exit_code::NOTHING_OUTDATED);
+    }
+
+    logger::print(format!(
+        "{total_outdated_deps} out of the {total_deps} direct dependencies are outdated.\n"
+    ));
+
+    let mut state = cli::State::new(dependencies, total_deps, &args);
+
+    if args.yes {
+        state.selected_dependencies().apply_versions(args)?;
+        return Ok(exit_code::SUCCESS);
+    }
+
+    state.start()?;
+
+    loop {
+        state.render()?;
+
+        match state.handle_keyboard_event()? {
+            cli::Event::HandleKeyboard => {}
+            cli::Event::UpdateDependencies => {
+                state.selected_dependencies().apply_versions(args)?;
+                return Ok(exit_code::SUCCESS);
+            }
+            cli::Event::Exit => {
+                return Ok(exit_code::ABORTED);
+            }
+        }
+    }
+}
+
+// Returns the retrieved dependencies along with the number of direct dependencies checked
+fn get_dependencies(
+    args: &args::Args,
+    current_member: Option<&str>,
+) -> Result<(dependency::Dependencies, usize), Box<dyn std::error::Error>> {
+    let mut dependencies = cargo::CargoDependencies::gather_dependencies(".")?;
+    if let (true, Some(member)) = (args.current_member, current_member) {
+        dependencies.filter_member(member);
+    } else if !args.workspace && args.package.is_empty() {
+        dependencies.filter_default_members();
+    }
+    dependencies.filter(&args.only, &args.exclude);
+    dependencies.filter_packages(&args.package)?;
+    if let Some((name, _)) = &args.precise {
+        dependencies.filter_crate(name);
+    }
+    let total_deps = dependencies.len();
+    args.hooks.run(
+        hooks::Hook::PreFetch,
+        hooks::get_pre_fetch_payload(&dependencies),
+    )?;
+    let retrieval = cargo::Retrieval {
+        // Watching polls for new releases, which a cached response would hide
+        use_cache: !args.no_cache && args.watch.is_none(),
+        lazy: args.lazy,
+    };
+    let mut dependencies = dependencies.retrieve_dependencies(None, retrieval)?;
+
+    // The list fetches the details of the rows it shows, but the reports and the minimum age need
+    // those of every outdated dependency, and the SBOM those of every component
+    if args.report_format() == Some(report::ReportFormat::CycloneDx) {
+        dependencies.load_details(|_| true);
+    } else if args.report_format().is_some() || args.precise.is_some() || args.min_age.is_some() {
+        dependencies.load_details(|dependency| {
+            dependency
+                .clone()
+                .resolve_latest_version(args.pre_releases)
+                .is_some()
+        });
+    }
+
+    if args.toolchain {
+        if let Some(toolchain) = toolchain::get_dependency()? {
+            dependencies.dependencies.push(toolchain);
+            return Ok((dependencies, total_deps + 1));
+        }
+    }
+
+    Ok((dependencies, total_deps))
+}
+
+// Editors run the command next to the manifest they show, so only the declarations of that
+// workspace member are looked up
+fn get_info(
+    name: &str,
+    current_member: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut dependencies = cargo::CargoDependencies::gather_dependencies(".")?;
+    if let Some(member) = current_member {
+        dependencies.filter_member(member);
+    }
+    dependencies.filter(&[name.to_string()], &[]);
+    if dependencies.is_empty() {
+        return Err(format!("`{name}` is not declared as a dependency").into());
+    }
+
+    let mut dependencies = dependencies.retrieve_dependencies(
+        None,
+        cargo::Retrieval {
+            use_cache: true,
+            lazy: false,
+        },
+    )?;
+    dependencies.load_details(|_| true);
+    Ok(report::render_info(&dependencies))
+}
+
+// The summaries are grouped by project, exiting with the highest of their exit codes
+fn check_projects(
+    args: &args::Args,
+    dir: &std::path::Path,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let dir = std::fs::canonicalize(dir)?;
+    let projects = cargo::find_projects(&dir);
+    if projects.is_empty() {
+        return Err(format!("No cargo projects found under {}", dir.display()).into());
+    }
+
+    let mut exit_code = exit_code::SUCCESS;
+    for project in projects {
+        let name = match project.strip_prefix(&dir) {
+            Ok(name) if !name.as_os_str().is_empty() => name.display().to_string(),
+            _ => ".".to_string(),
+        };
+        logger::print(format!("{}\n", args.theme.emphasis(name)));
+
+        std::env::set_current_dir(&project)?;
+        let project_exit_code = match get_dependencies(args, None) {
+            Ok((dependencies, total_deps)) => {
+                let outdated_deps = get_outdated_dependencies(&dependencies, args);
+                logger::print(format!(
+                    "{}\n",
+                    report::render_check_summary(&outdated_deps, total_deps)
+                ));
+                args.fail_on
+                    .unwrap_or(report::FailOn::Outdated)
+                    .get_exit_code(&outdated_deps)
+            }
+            Err(error) => {
+                eprintln!("Error: {error}\n");
+                exit_code::get_exit_code(error.as_ref())
+            }
+        };
+        exit_code = exit_code.max(project_exit_code);
+    }
+
+    Ok(exit_code)
+}
+
+// Only prints the summary when it differs from the previous check, errors such as a network
+// failure are printed as well without stopping the watch
+fn watch(args: &args::Args, current_member: Option<&str>, interval: std::time::Duration) -> ! {
+    let mut previous_summary = None;
+
+    loop {
+        let summary = match get_dependencies(args, current_member) {
+            Ok((dependencies, total_deps)) => report::render_check_summary(
+                &get_outdated_dependencies(&dependencies, args),
+                total_deps,
+            ),
+            Err(error) => format!("Error: {error}\n"),
+        };
+
+        if previous_summary.as_ref() != Some(&summary) {
+            println!("[{}] {summary}", history::get_current_timestamp());
+            logger::print_warnings();
+            previous_summary = Some(summary);
+        } else {
+            logger::take_warnings();
+        }
+
+        std::thread::sleep(interval);
+    }
+}
+
+fn get_outdated_dependencies(
+    dependencies: &dependency::Dependencies,
+    args: &args::Args,
+) -> dependency::Dependencies {
+    // Listings are only grouped by section when sorting by kind
+    let section_order = match args.sort {
+        dependency::SortOrder::Kind => {
+            dependency::DependencyKind::complete_order(&args.section_order)
+        }
+        _ => vec![],
+    };
+    let mut outdated_deps = dependencies.outdated(args.pre_releases);
+    policy::Policy::from_args(args).apply(&mut outdated_deps);
+    outdated_deps.sort(&section_order, args.sort);
+    outdated_deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_options() {
+        let args = args::Args::from(UpdateOptions::default());
+        assert!(args.yes);
+        assert_eq!(args.verify_command(), dependency::VerifyCommand::Check);
+        assert!(!args.no_lockfile && !args.no_history && !args.commit);
+
+        let args = args::Args::from(UpdateOptions {
+            verify: false,
+            update_lockfile: false,
+            commit: true,
+            ..Default::default()
+        });
+        assert_eq!(args.verify_command(), dependency::VerifyCommand::None);
+        assert!(args.no_lockfile && args.commit);
+    }
+}
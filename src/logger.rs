@@ -0,0 +1,76 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Mutex,
+};
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+const INFO: u8 = 1;
+const DEBUG: u8 = 2;
+
+pub fn set_verbosity(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn print(message: impl AsRef<str>) {
+    if !QUIET.load(Ordering::Relaxed) {
+        print!("{}", message.as_ref());
+    }
+}
+
+// Warnings are printed once the command is done, so they aren't hidden by the interactive list
+pub fn warn(message: impl AsRef<str>) {
+    if let Ok(mut warnings) = WARNINGS.lock() {
+        warnings.push(message.as_ref().to_string());
+    }
+}
+
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS
+        .lock()
+        .map(|mut warnings| std::mem::take(&mut *warnings))
+        .unwrap_or_default()
+}
+
+pub fn print_warnings() {
+    for warning in take_warnings() {
+        eprintln!("Warning: {warning}");
+    }
+}
+
+pub fn info(message: impl AsRef<str>) {
+    log(INFO, "info", message.as_ref());
+}
+
+pub fn debug(message: impl AsRef<str>) {
+    log(DEBUG, "debug", message.as_ref());
+}
+
+fn log(level: u8, label: &str, message: &str) {
+    if is_enabled(VERBOSITY.load(Ordering::Relaxed), level) {
+        eprintln!("[{label}] {message}");
+    }
+}
+
+fn is_enabled(verbosity: u8, level: u8) -> bool {
+    verbosity >= level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_enabled() {
+        assert!(!is_enabled(0, INFO));
+        assert!(is_enabled(1, INFO));
+        assert!(!is_enabled(1, DEBUG));
+        assert!(is_enabled(3, DEBUG));
+    }
+}
@@ -0,0 +1,373 @@
+use semver::Version;
+use std::time::{SystemTime, UNIX_EPOCH};
+use toml_edit::DocumentMut;
+
+use crate::{
+    args::Args,
+    dependency::{Dependencies, Dependency},
+    rules::{self, Rule},
+    select::Expression,
+};
+
+#[derive(Clone, Default)]
+pub struct Policy {
+    pub ignore: Vec<String>,
+    pub min_age_days: Option<u64>,
+    pub compatible_only: bool,
+    pub respect_rust_version: bool,
+    pub ignore_yanked: bool,
+    pub only_yanked: bool,
+    pub security: bool,
+    pub select: Option<Expression>,
+    pub rules: Vec<Rule>,
+}
+
+impl Policy {
+    pub fn from_args(args: &Args) -> Self {
+        Self {
+            ignore: args.ignore.clone(),
+            min_age_days: args.min_age,
+            compatible_only: args.compatible_only,
+            respect_rust_version: args.respect_rust_version,
+            ignore_yanked: args.ignore_yanked,
+            only_yanked: args.only_yanked,
+            security: args.security,
+            select: args.select.clone(),
+            rules: args.rules.clone(),
+        }
+    }
+
+    pub fn apply(&self, dependencies: &mut Dependencies) {
+        dependencies
+            .dependencies
+            .retain(|dependency| self.is_listed(dependency));
+
+        let today = get_days_since_epoch_today();
+        let root_rust_version = dependencies
+            .cargo_toml_files
+            .get(".")
+            .and_then(|cargo_toml| get_workspace_rust_version(cargo_toml));
+
+        for i in 0..dependencies.dependencies.len() {
+            let dependency = &dependencies.dependencies[i];
+            // Already held back for being from an unknown source
+            if dependency.unknown_source.is_some() {
+                continue;
+            }
+            let rust_version = dependencies
+                .cargo_toml_files
+                .get(dependency.workspace_path.as_deref().unwrap_or("."))
+                .and_then(|cargo_toml| get_package_rust_version(cargo_toml))
+                .or_else(|| root_rust_version.clone());
+
+            let rule = rules::resolve(&self.rules, dependency);
+            let held_back = self
+                .get_held_back_reason(dependency, rust_version.as_ref(), today)
+                .or_else(|| rule.get_held_back_reason(dependency, today));
+            dependencies.dependencies[i].held_back = held_back;
+            dependencies.dependencies[i].group = rule.group;
+            // The selection expression and the security mode override the rules, the latter only
+            // selecting the fixes of known advisories
+            let dependency = &dependencies.dependencies[i];
+            let fixes_advisories = !dependency.advisories.is_empty();
+            dependencies.dependencies[i].auto_select = match &self.select {
+                Some(select) => {
+                    Some(select.matches(dependency) && (!self.security || fixes_advisories))
+                }
+                None if self.security => Some(fixes_advisories),
+                None => rule.select,
+            };
+        }
+    }
+
+    fn is_listed(&self, dependency: &Dependency) -> bool {
+        if self.only_yanked {
+            return dependency.is_current_version_yanked();
+        }
+
+        !self.ignore_yanked || dependency.is_newer_version_available()
+    }
+
+    fn get_held_back_reason(
+        &self,
+        dependency: &Dependency,
+        rust_version: Option<&Version>,
+        today: i64,
+    ) -> Option<String> {
+        if self.ignore.contains(&dependency.name) {
+            return Some("ignored".to_string());
+        }
+
+        if self.compatible_only && dependency.is_breaking_update() {
+            return Some("breaking update, only compatible updates are allowed".to_string());
+        }
+
+        if let Some(min_age_days) = self.min_age_days {
+            let released = dependency
+                .latest_version_date
+                .as_deref()
+                .and_then(get_days_since_epoch);
+
+            if let Some(released) = released {
+                let age = today - released;
+                if age < min_age_days as i64 {
+                    return Some(format!(
+                        "released {age} day{} ago, below the minimum age of {min_age_days} days",
+                        if age == 1 { "" } else { "s" }
+                    ));
+                }
+            }
+        }
+
+        if self.respect_rust_version {
+            let required = dependency.latest_rust_version();
+            let required_version = required.and_then(parse_rust_version);
+
+            if let (Some(required), Some(required_version), Some(rust_version)) =
+                (required, required_version, rust_version)
+            {
+                if required_version > *rust_version {
+                    return Some(format!(
+                        "requires rust {required}, project uses {rust_version}"
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_rust_version(rust_version: &str) -> Option<Version> {
+    let rust_version = rust_version.trim();
+
+    match rust_version.matches('.').count() {
+        0 => Version::parse(&format!("{rust_version}.0.0")).ok(),
+        1 => Version::parse(&format!("{rust_version}.0")).ok(),
+        _ => Version::parse(rust_version).ok(),
+    }
+}
+
+fn get_package_rust_version(cargo_toml: &DocumentMut) -> Option<Version> {
+    parse_rust_version(cargo_toml.get("package")?.get("rust-version")?.as_str()?)
+}
+
+fn get_workspace_rust_version(cargo_toml: &DocumentMut) -> Option<Version> {
+    parse_rust_version(
+        cargo_toml
+            .get("workspace")?
+            .get("package")?
+            .get("rust-version")?
+            .as_str()?,
+    )
+    .or_else(|| get_package_rust_version(cargo_toml))
+}
+
+pub fn get_days_since_epoch_today() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| (duration.as_secs() / 86_400) as i64)
+}
+
+// Based on Howard Hinnant's `days_from_civil` algorithm
+fn get_days_since_epoch(datetime: &str) -> Option<i64> {
+    let mut parts = datetime.get(..10)?.split('-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<i64>().ok()?;
+    let day = parts.next()?.parse::<i64>().ok()?;
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    Some(era * 146_097 + day_of_era - 719_468)
+}
+
+// Based on Howard Hinnant's `civil_from_days` algorithm
+pub fn get_date_from_days_since_epoch(days: i64) -> String {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RegistryVersion;
+
+    #[test]
+    fn test_get_days_since_epoch() {
+        assert_eq!(get_days_since_epoch("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(get_days_since_epoch("2000-03-01"), Some(11_017));
+        assert_eq!(
+            get_days_since_epoch("2024-09-04T12:00:00.000000+00:00"),
+            Some(19_970)
+        );
+        assert_eq!(get_days_since_epoch("invalid"), None);
+    }
+
+    #[test]
+    fn test_get_date_from_days_since_epoch() {
+        assert_eq!(get_date_from_days_since_epoch(0), "1970-01-01");
+        assert_eq!(get_date_from_days_since_epoch(11_017), "2000-03-01");
+        assert_eq!(get_date_from_days_since_epoch(19_970), "2024-09-04");
+        assert_eq!(get_date_from_days_since_epoch(19_782), "2024-02-29");
+    }
+
+    #[test]
+    fn test_parse_rust_version() {
+        assert_eq!(parse_rust_version("1"), Some(Version::new(1, 0, 0)));
+        assert_eq!(parse_rust_version("1.70"), Some(Version::new(1, 70, 0)));
+        assert_eq!(parse_rust_version("1.70.1"), Some(Version::new(1, 70, 1)));
+        assert_eq!(parse_rust_version("stable"), None);
+    }
+
+    #[test]
+    fn test_get_held_back_reason() {
+        let dependency = Dependency {
+            name: "serde".to_string(),
+            latest_version: "1.0.200".to_string(),
+            latest_version_date: Some("2024-01-10T00:00:00Z".to_string()),
+            versions: vec![RegistryVersion {
+                num: "1.0.200".to_string(),
+                rust_version: Some("1.80".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let today = get_days_since_epoch("2024-01-12").unwrap();
+        let rust_version = Version::new(1, 70, 0);
+
+        assert_eq!(
+            Policy::default().get_held_back_reason(&dependency, Some(&rust_version), today),
+            None
+        );
+
+        let policy = Policy {
+            ignore: vec!["serde".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.get_held_back_reason(&dependency, None, today),
+            Some("ignored".to_string())
+        );
+
+        let policy = Policy {
+            compatible_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.get_held_back_reason(
+                &Dependency {
+                    current_version: "0.9.0".to_string(),
+                    ..dependency.clone()
+                },
+                None,
+                today
+            ),
+            Some("breaking update, only compatible updates are allowed".to_string())
+        );
+        assert_eq!(
+            policy.get_held_back_reason(
+                &Dependency {
+                    current_version: "1.0.100".to_string(),
+                    ..dependency.clone()
+                },
+                None,
+                today
+            ),
+            None
+        );
+
+        let policy = Policy {
+            min_age_days: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.get_held_back_reason(&dependency, None, today),
+            Some("released 2 days ago, below the minimum age of 7 days".to_string())
+        );
+
+        let policy = Policy {
+            respect_rust_version: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.get_held_back_reason(&dependency, Some(&rust_version), today),
+            Some("requires rust 1.80, project uses 1.70.0".to_string())
+        );
+        assert_eq!(policy.get_held_back_reason(&dependency, None, today), None);
+    }
+
+    #[test]
+    fn test_is_listed() {
+        let yanked = RegistryVersion {
+            num: "1.0.0".to_string(),
+            yanked: true,
+            ..Default::default()
+        };
+        let updatable = Dependency {
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            ..Default::default()
+        };
+        let yanked_updatable = Dependency {
+            versions: vec![yanked.clone()],
+            ..updatable.clone()
+        };
+        let yanked_only = Dependency {
+            latest_version: "1.0.0".to_string(),
+            ..yanked_updatable.clone()
+        };
+
+        let policy = Policy::default();
+        assert!(policy.is_listed(&updatable));
+        assert!(policy.is_listed(&yanked_only));
+
+        let policy = Policy {
+            ignore_yanked: true,
+            ..Default::default()
+        };
+        assert!(policy.is_listed(&updatable));
+        assert!(policy.is_listed(&yanked_updatable));
+        assert!(!policy.is_listed(&yanked_only));
+
+        let policy = Policy {
+            only_yanked: true,
+            ..Default::default()
+        };
+        assert!(!policy.is_listed(&updatable));
+        assert!(policy.is_listed(&yanked_updatable));
+        assert!(policy.is_listed(&yanked_only));
+    }
+
+    #[test]
+    fn test_get_workspace_rust_version() {
+        let cargo_toml: DocumentMut = r#"
+        [workspace.package]
+        rust-version = "1.75"
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            get_workspace_rust_version(&cargo_toml),
+            Some(Version::new(1, 75, 0))
+        );
+        assert_eq!(get_package_rust_version(&cargo_toml), None);
+    }
+}
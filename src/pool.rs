@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+
+// Most of the work is bound by the network requests, so more workers than cores keeps them busy
+// without spawning a thread per dependency on large workspaces
+const MAX_WORKERS: usize = 16;
+
+// Runs `f` on every item with a fixed number of worker threads pulling from a shared queue,
+// returning the results in the order of the items. A panic is carried over to the caller, which
+// zips the results back onto the items and can't miss any of them.
+pub fn map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let workers = MAX_WORKERS.min(items.len());
+    let queue = Mutex::new(items.into_iter().enumerate());
+
+    let mut results = std::thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let Some((index, item)) = queue.lock().unwrap().next() else {
+                            break;
+                        };
+                        results.push((index, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        assert_eq!(
+            map((0..100).collect(), |i: usize| i * 2),
+            (0..100).map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(map(vec![], |i: usize| i), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_map_panic() {
+        let result = std::panic::catch_unwind(|| {
+            map((0..100).collect(), |i: usize| {
+                assert_ne!(i, 42, "worker panic");
+                i
+            })
+        });
+
+        let error = result.unwrap_err();
+        assert!(error
+            .downcast_ref::<String>()
+            .is_some_and(|message| message.contains("worker panic")));
+    }
+}
@@ -0,0 +1,1017 @@
+use serde_json::json;
+
+use crate::{
+    changelog,
+    cli::Longest,
+    dependency::{Dependencies, Dependency, DependencyKind},
+    exit_code, security,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+    Github,
+    Markdown,
+    Porcelain,
+    Sarif,
+    Summary,
+}
+
+const PORCELAIN_VERSION: u32 = 1;
+const SARIF_RULES: [(&str, &str, &str); 3] = [
+    (
+        "outdated-dependency",
+        "A compatible update of the dependency is available",
+        "note",
+    ),
+    (
+        "breaking-update",
+        "A breaking update of the dependency is available",
+        "warning",
+    ),
+    (
+        "yanked-version",
+        "The current version of the dependency has been yanked",
+        "error",
+    ),
+];
+const SUMMARY_COLUMNS: [&str; 7] = [
+    "Name", "Member", "Kind", "Current", "Latest", "Released", "Notes",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum FailOn {
+    Outdated,
+    Breaking,
+    Vulnerable,
+    None,
+}
+
+impl FailOn {
+    pub fn get_exit_code(self, outdated_deps: &Dependencies) -> i32 {
+        let exit_code = get_check_exit_code(outdated_deps);
+
+        match self {
+            FailOn::Outdated => exit_code,
+            FailOn::Breaking if exit_code == exit_code::OUTDATED_BREAKING => exit_code,
+            FailOn::Vulnerable
+                if outdated_deps
+                    .iter()
+                    .any(|dependency| dependency.is_current_version_yanked()) =>
+            {
+                exit_code::VULNERABLE
+            }
+            FailOn::Breaking | FailOn::Vulnerable | FailOn::None => exit_code::SUCCESS,
+        }
+    }
+}
+
+pub fn render(
+    format: ReportFormat,
+    dependencies: &Dependencies,
+    outdated_deps: &Dependencies,
+    total_deps: usize,
+) -> String {
+    match format {
+        ReportFormat::Json => render_json(outdated_deps, total_deps),
+        ReportFormat::CycloneDx => render_cyclonedx(dependencies, outdated_deps),
+        ReportFormat::Github => render_github(outdated_deps),
+        ReportFormat::Markdown => render_markdown(outdated_deps, total_deps),
+        ReportFormat::Porcelain => render_porcelain(outdated_deps, total_deps),
+        ReportFormat::Sarif => render_sarif(outdated_deps),
+        ReportFormat::Summary => render_summary(outdated_deps, total_deps),
+    }
+}
+
+fn get_crates_io_url(dependency: &Dependency) -> String {
+    format!("https://crates.io/crates/{}", dependency.crate_name())
+}
+
+pub fn get_dependency_json(dependency: &Dependency) -> serde_json::Value {
+    json!({
+        "name": dependency.name,
+        "kind": dependency.kind.name(),
+        "member": dependency.package_name.as_deref().filter(|name| !name.is_empty()),
+        "manifest_path": dependency.manifest_path(),
+        "current_version": dependency.current_version,
+        "current_version_date": dependency.current_version_date,
+        "current_version_yanked": dependency.is_current_version_yanked(),
+        "latest_version": dependency.latest_version,
+        "latest_version_date": dependency.latest_version_date,
+        "magnitude": dependency.magnitude().map(|magnitude| magnitude.name()),
+        "held_back": dependency.held_back,
+        "unknown_source": dependency.unknown_source.is_some(),
+        "possibly_unused": dependency.possibly_unused,
+        "advisories": dependency
+            .advisories
+            .iter()
+            .map(|advisory| advisory.id.as_str())
+            .collect::<Vec<_>>(),
+        "severity": security::get_severity(dependency).map(|severity| severity.name()),
+        "repository": dependency.repository,
+        "description": dependency.description,
+        "crates_io_url": get_crates_io_url(dependency),
+    })
+}
+
+// Every declaration of a single crate, whether outdated or not, for editors to show inline
+pub fn render_info(dependencies: &Dependencies) -> String {
+    let declarations = dependencies
+        .iter()
+        .map(|dependency| {
+            let mut declaration = get_dependency_json(dependency);
+            declaration["outdated"] = dependency.is_newer_version_available().into();
+            declaration
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&declarations).unwrap_or_default()
+}
+
+fn render_json(outdated_deps: &Dependencies, total_deps: usize) -> String {
+    let outdated = outdated_deps
+        .iter()
+        .map(get_dependency_json)
+        .collect::<Vec<_>>();
+
+    let report = json!({
+        "total_dependencies": total_deps,
+        "outdated_dependencies": outdated,
+    });
+
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+// CycloneDX 1.5 bill of materials of every direct dependency, with the update status of each in
+// properties since the specification has no field for it
+fn render_cyclonedx(dependencies: &Dependencies, outdated_deps: &Dependencies) -> String {
+    let components = dependencies
+        .iter()
+        .filter(|dependency| dependency.kind != DependencyKind::Toolchain)
+        .map(|dependency| {
+            let outdated = outdated_deps.iter().find(|outdated| {
+                outdated.name == dependency.name
+                    && outdated.kind == dependency.kind
+                    && outdated.workspace_path == dependency.workspace_path
+            });
+            let purl = format!(
+                "pkg:cargo/{}@{}",
+                dependency.crate_name(),
+                dependency.current_version
+            );
+
+            let mut properties = vec![
+                ("kind", dependency.kind.name().to_string()),
+                ("manifest_path", dependency.manifest_path()),
+                (
+                    "latest_version",
+                    outdated
+                        .unwrap_or(dependency)
+                        .latest_version
+                        .to_string(),
+                ),
+                ("outdated", outdated.is_some().to_string()),
+                (
+                    "current_version_yanked",
+                    dependency.is_current_version_yanked().to_string(),
+                ),
+            ];
+            if let Some(held_back) = outdated.and_then(|outdated| outdated.held_back.clone()) {
+                properties.push(("held_back", held_back));
+            }
+
+            let mut external_references = vec![json!({
+                "type": "distribution",
+                "url": get_crates_io_url(dependency),
+            })];
+            if let Some(repository) = &dependency.repository {
+                external_references.push(json!({ "type": "vcs", "url": repository }));
+            }
+
+            json!({
+                "type": "library",
+                "bom-ref": format!("{}#{}", dependency.manifest_path(), dependency.name),
+                "name": dependency.crate_name(),
+                "version": dependency.current_version,
+                "description": dependency.description,
+                "scope": if dependency.kind == DependencyKind::Dev { "excluded" } else { "required" },
+                "purl": purl,
+                "externalReferences": external_references,
+                "properties": properties
+                    .into_iter()
+                    .map(|(name, value)| json!({
+                        "name": format!("cargo-interactive-update:{name}"),
+                        "value": value,
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let bom = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+        },
+        "components": components,
+    });
+
+    serde_json::to_string_pretty(&bom).unwrap_or_default()
+}
+
+// SARIF 2.1.0 log for code scanning dashboards, with a result per outdated dependency pointing at
+// the line declaring it
+fn render_sarif(outdated_deps: &Dependencies) -> String {
+    let rules = SARIF_RULES
+        .iter()
+        .map(|(id, description, level)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = outdated_deps
+        .iter()
+        .map(|dependency| {
+            let (rule_id, level) = if dependency.is_current_version_yanked() {
+                ("yanked-version", "error")
+            } else if dependency.is_breaking_update() {
+                ("breaking-update", "warning")
+            } else {
+                ("outdated-dependency", "note")
+            };
+
+            let mut message = format!(
+                "{} {} -> {}",
+                dependency.name, dependency.current_version, dependency.latest_version
+            );
+            if dependency.is_current_version_yanked() {
+                message.push_str(", current version yanked");
+            }
+            if let Some(held_back) = &dependency.held_back {
+                message.push_str(&format!(", held back: {held_back}"));
+            }
+
+            let mut physical_location = json!({
+                "artifactLocation": {
+                    "uri": dependency.manifest_path().trim_start_matches("./"),
+                },
+            });
+            if let Some(line) = outdated_deps.get_declaration_line(dependency) {
+                physical_location["region"] = json!({ "startLine": line });
+            }
+
+            json!({
+                "ruleId": rule_id,
+                "level": level,
+                "message": { "text": message },
+                "locations": [{ "physicalLocation": physical_location }],
+                "properties": {
+                    "kind": dependency.kind.name(),
+                    "currentVersion": dependency.current_version,
+                    "latestVersion": dependency.latest_version,
+                    "heldBack": dependency.held_back.is_some(),
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+// GitHub Actions workflow commands, shown as annotations on the manifest lines of pull requests
+fn render_github(outdated_deps: &Dependencies) -> String {
+    outdated_deps
+        .iter()
+        .map(|dependency| {
+            let level = if dependency.is_current_version_yanked() {
+                "error"
+            } else if dependency.held_back.is_some() {
+                "notice"
+            } else {
+                "warning"
+            };
+
+            let mut properties = vec![format!(
+                "file={}",
+                escape_github_property(dependency.manifest_path().trim_start_matches("./"))
+            )];
+            if let Some(line) = outdated_deps.get_declaration_line(dependency) {
+                properties.push(format!("line={line}"));
+            }
+
+            let mut message = format!(
+                "{} {} -> {}",
+                dependency.name, dependency.current_version, dependency.latest_version
+            );
+            if dependency.is_breaking_update() {
+                message.push_str(" (breaking)");
+            }
+            if dependency.is_current_version_yanked() {
+                message.push_str(", current version yanked");
+            }
+            if let Some(held_back) = &dependency.held_back {
+                message.push_str(&format!(", held back: {held_back}"));
+            }
+
+            format!(
+                "::{level} {}::{}",
+                properties.join(","),
+                escape_github_data(&message)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape_github_data(content: &str) -> String {
+    content
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_github_property(content: &str) -> String {
+    escape_github_data(content)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn get_releases_url(dependency: &Dependency) -> Option<String> {
+    let (owner, repo) = changelog::get_github_repository(dependency.repository.as_deref()?)?;
+    Some(format!("https://github.com/{owner}/{repo}/releases"))
+}
+
+fn escape_markdown_cell(content: &str) -> String {
+    content.replace('|', "\\|")
+}
+
+fn render_markdown(outdated_deps: &Dependencies, total_deps: usize) -> String {
+    if outdated_deps.is_empty() {
+        return format!("All {total_deps} direct dependencies are up to date!");
+    }
+
+    format!(
+        "{} out of the {total_deps} direct dependencies are outdated.\n\n{}",
+        outdated_deps.len(),
+        render_markdown_table(outdated_deps)
+    )
+}
+
+pub fn render_markdown_table(dependencies: &Dependencies) -> String {
+    let mut lines = vec![
+        "| Crate | Kind | Member | Current | Latest | Released | Links | Notes |".to_string(),
+        "| --- | --- | --- | --- | --- | --- | --- | --- |".to_string(),
+    ];
+
+    for dependency in dependencies.iter() {
+        let mut links = vec![];
+        if let Some(repository) = &dependency.repository {
+            links.push(format!("[repository]({repository})"));
+        }
+        if let Some(releases_url) = get_releases_url(dependency) {
+            links.push(format!("[changelog]({releases_url})"));
+        }
+
+        let mut notes = vec![];
+        if dependency.is_current_version_yanked() {
+            notes.push("current version yanked".to_string());
+        }
+        if let Some(held_back) = &dependency.held_back {
+            notes.push(format!("held back: {held_back}"));
+        }
+        if dependency.possibly_unused {
+            notes.push("possibly unused".to_string());
+        }
+
+        lines.push(format!(
+            "| [{}]({}) | {} | {} | {} | {} | {} | {} | {} |",
+            dependency.name,
+            get_crates_io_url(dependency),
+            dependency.kind.name(),
+            dependency
+                .package_name
+                .as_deref()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("-"),
+            dependency.current_version,
+            dependency.latest_version,
+            dependency
+                .latest_version_date
+                .as_deref()
+                .and_then(|date| date.get(..10))
+                .unwrap_or("-"),
+            links.join(" "),
+            escape_markdown_cell(&notes.join(", ")),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn render_porcelain(outdated_deps: &Dependencies, total_deps: usize) -> String {
+    let mut lines = vec![
+        format!("porcelain\tv{PORCELAIN_VERSION}"),
+        format!("total\t{total_deps}\t{}", outdated_deps.len()),
+    ];
+
+    for dependency in outdated_deps.iter() {
+        let mut flags = vec![];
+        if dependency.is_current_version_yanked() {
+            flags.push("yanked");
+        }
+        if dependency.held_back.is_some() {
+            flags.push("held-back");
+        }
+
+        lines.push(
+            [
+                "dependency",
+                &dependency.name,
+                dependency.kind.name(),
+                dependency
+                    .package_name
+                    .as_deref()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("-"),
+                &dependency.manifest_path(),
+                &dependency.current_version,
+                &dependency.latest_version,
+                dependency
+                    .magnitude()
+                    .map_or("-", |magnitude| magnitude.name()),
+                &if flags.is_empty() {
+                    "-".to_string()
+                } else {
+                    flags.join(",")
+                },
+            ]
+            .join("\t"),
+        );
+    }
+
+    lines.join("\n")
+}
+
+fn render_summary(outdated_deps: &Dependencies, total_deps: usize) -> String {
+    if outdated_deps.is_empty() {
+        return format!("All {total_deps} direct dependencies are up to date!");
+    }
+
+    let longest = Longest::get_longest_attributes(outdated_deps);
+    let show_members = outdated_deps.has_workspace_members();
+
+    let rows = outdated_deps.iter().map(|dependency| {
+        let mut notes = vec![];
+        if dependency.is_current_version_yanked() {
+            notes.push("current version yanked".to_string());
+        }
+        if let Some(held_back) = &dependency.held_back {
+            notes.push(format!("held back: {held_back}"));
+        }
+        if dependency.possibly_unused {
+            notes.push("possibly unused".to_string());
+        }
+
+        [
+            dependency.name.to_string(),
+            dependency
+                .package_name
+                .as_deref()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("-")
+                .to_string(),
+            dependency.kind.name().to_string(),
+            dependency.current_version.to_string(),
+            dependency.latest_version.to_string(),
+            dependency
+                .latest_version_date
+                .as_deref()
+                .and_then(|date| date.get(..10))
+                .unwrap_or("-")
+                .to_string(),
+            notes.join(", "),
+        ]
+    });
+    let header = SUMMARY_COLUMNS.map(String::from);
+    let separator = SUMMARY_COLUMNS.map(|title| "-".repeat(title.len()));
+    let widths = [
+        longest.name,
+        longest.package_name,
+        "workspace".len(),
+        longest.current_version,
+        longest.latest_version,
+        "YYYY-MM-DD".len(),
+        0,
+    ]
+    .into_iter()
+    .zip(SUMMARY_COLUMNS)
+    .map(|(width, title)| width.max(title.len()))
+    .collect::<Vec<_>>();
+
+    let mut lines = vec![
+        format!(
+            "{} out of the {total_deps} direct dependencies are outdated.",
+            outdated_deps.len()
+        ),
+        String::new(),
+    ];
+
+    for row in [header, separator].into_iter().chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .filter(|(i, _)| *i != 1 || show_members)
+            .map(|(_, (cell, width))| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        lines.push(line.trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+pub fn get_check_exit_code(outdated_deps: &Dependencies) -> i32 {
+    let updatable = outdated_deps
+        .iter()
+        .filter(|dependency| dependency.held_back.is_none())
+        .collect::<Vec<_>>();
+
+    if updatable.is_empty() {
+        exit_code::SUCCESS
+    } else if updatable
+        .iter()
+        .any(|dependency| dependency.is_breaking_update())
+    {
+        exit_code::OUTDATED_BREAKING
+    } else {
+        exit_code::OUTDATED_COMPATIBLE
+    }
+}
+
+pub fn render_check_summary(outdated_deps: &Dependencies, total_deps: usize) -> String {
+    let (held_back, updatable): (Vec<_>, Vec<_>) = outdated_deps
+        .iter()
+        .partition(|dependency| dependency.held_back.is_some());
+    let unknown = held_back
+        .iter()
+        .filter(|dependency| dependency.unknown_source.is_some())
+        .count();
+
+    if updatable.is_empty() && unknown == 0 {
+        return format!("All {total_deps} direct dependencies are up to date!\n");
+    }
+    if updatable.is_empty() {
+        return format!(
+            "{} of the {total_deps} direct dependencies are up to date!\n{unknown} from an unknown source.\n",
+            total_deps.saturating_sub(unknown)
+        );
+    }
+
+    let breaking = updatable
+        .iter()
+        .filter(|dependency| dependency.is_breaking_update())
+        .count();
+
+    let mut summary = format!(
+        "{} out of the {total_deps} direct dependencies are outdated ({} compatible, {breaking} breaking).\n",
+        updatable.len(),
+        updatable.len() - breaking,
+    );
+
+    for dependency in updatable {
+        summary.push_str(&format!(
+            "  {} {} -> {}{}\n",
+            dependency.name,
+            dependency.current_version,
+            dependency.latest_version,
+            if dependency.is_breaking_update() {
+                " (breaking)"
+            } else {
+                ""
+            }
+        ));
+    }
+
+    if held_back.len() > unknown {
+        summary.push_str(&format!(
+            "{} held back by policy.\n",
+            held_back.len() - unknown
+        ));
+    }
+    if unknown > 0 {
+        summary.push_str(&format!("{unknown} from an unknown source.\n"));
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RegistryVersion;
+    use std::collections::HashMap;
+
+    fn dependency(name: &str, current_version: &str, latest_version: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            current_version: current_version.to_string(),
+            latest_version: latest_version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_info() {
+        let dependencies = Dependencies::new(
+            vec![Dependency {
+                name: "serde".to_string(),
+                current_version: "1.0.0".to_string(),
+                latest_version: "1.0.0".to_string(),
+                ..Default::default()
+            }],
+            HashMap::new(),
+        );
+
+        let info: serde_json::Value = serde_json::from_str(&render_info(&dependencies)).unwrap();
+
+        assert_eq!(info.as_array().map(Vec::len), Some(1));
+        assert_eq!(info[0]["name"], "serde");
+        assert_eq!(info[0]["manifest_path"], "./Cargo.toml");
+        assert_eq!(info[0]["outdated"], false);
+        assert_eq!(info[0]["current_version_yanked"], false);
+    }
+
+    #[test]
+    fn test_render_json() {
+        let dependencies = Dependencies::new(
+            vec![Dependency {
+                name: "serde".to_string(),
+                current_version: "1.0.0".to_string(),
+                latest_version: "1.1.0".to_string(),
+                latest_version_date: Some("2024-01-01T00:00:00Z".to_string()),
+                repository: Some("https://github.com/serde-rs/serde".to_string()),
+                kind: DependencyKind::Dev,
+                package_name: Some("".to_string()),
+                workspace_path: Some("crates/foo".to_string()),
+                ..Default::default()
+            }],
+            HashMap::new(),
+        );
+
+        let report: serde_json::Value =
+            serde_json::from_str(&render(ReportFormat::Json, &dependencies, &dependencies, 3))
+                .unwrap();
+
+        assert_eq!(report["total_dependencies"], 3);
+        let dependency = &report["outdated_dependencies"][0];
+        assert_eq!(dependency["name"], "serde");
+        assert_eq!(dependency["kind"], "dev");
+        assert_eq!(dependency["member"], serde_json::Value::Null);
+        assert_eq!(dependency["manifest_path"], "./crates/foo/Cargo.toml");
+        assert_eq!(dependency["current_version"], "1.0.0");
+        assert_eq!(dependency["latest_version"], "1.1.0");
+        assert_eq!(dependency["latest_version_date"], "2024-01-01T00:00:00Z");
+        assert_eq!(dependency["magnitude"], "minor");
+        assert_eq!(dependency["current_version_yanked"], false);
+        assert_eq!(
+            dependency["repository"],
+            "https://github.com/serde-rs/serde"
+        );
+        assert_eq!(
+            dependency["crates_io_url"],
+            "https://crates.io/crates/serde"
+        );
+    }
+
+    #[test]
+    fn test_render_cyclonedx() {
+        let mut serde = dependency("serde", "1.0.0", "1.0.0");
+        serde.repository = Some("https://github.com/serde-rs/serde".to_string());
+        let mut json = dependency("json", "1.0.0", "1.0.1");
+        json.package = Some("serde_json".to_string());
+        json.kind = DependencyKind::Dev;
+        let dependencies = Dependencies::new(vec![serde, json.clone()], HashMap::new());
+        let outdated_deps = Dependencies::new(vec![json], HashMap::new());
+
+        let bom: serde_json::Value = serde_json::from_str(&render(
+            ReportFormat::CycloneDx,
+            &dependencies,
+            &outdated_deps,
+            2,
+        ))
+        .unwrap();
+
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], "1.5");
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+
+        assert_eq!(components[0]["name"], "serde");
+        assert_eq!(components[0]["purl"], "pkg:cargo/serde@1.0.0");
+        assert_eq!(components[0]["scope"], "required");
+        assert_eq!(components[0]["externalReferences"][1]["type"], "vcs");
+        assert_eq!(
+            components[0]["properties"][3],
+            serde_json::json!({ "name": "cargo-interactive-update:outdated", "value": "false" })
+        );
+
+        assert_eq!(components[1]["name"], "serde_json");
+        assert_eq!(components[1]["bom-ref"], "./Cargo.toml#json");
+        assert_eq!(components[1]["scope"], "excluded");
+        assert_eq!(
+            components[1]["properties"][2],
+            serde_json::json!({ "name": "cargo-interactive-update:latest_version", "value": "1.0.1" })
+        );
+        assert_eq!(
+            components[1]["properties"][3],
+            serde_json::json!({ "name": "cargo-interactive-update:outdated", "value": "true" })
+        );
+    }
+
+    #[test]
+    fn test_render_sarif() {
+        let mut breaking = dependency("rand", "0.8.0", "0.9.0");
+        breaking.workspace_path = Some("crates/foo".to_string());
+        let mut yanked = dependency("serde", "1.0.0", "1.0.0");
+        yanked.versions = vec![RegistryVersion {
+            num: "1.0.0".to_string(),
+            yanked: true,
+            ..Default::default()
+        }];
+        let outdated_deps = Dependencies::new(
+            vec![dependency("log", "0.4.0", "0.4.1"), breaking, yanked],
+            HashMap::from([
+                (
+                    ".".to_string(),
+                    "[dependencies]\nlog = \"0.4\"\nserde = \"1.0\"\n"
+                        .parse()
+                        .unwrap(),
+                ),
+                (
+                    "crates/foo".to_string(),
+                    "[package]\nname = \"foo\"\n\n[dependencies]\nrand = \"0.8\"\n"
+                        .parse()
+                        .unwrap(),
+                ),
+            ]),
+        );
+
+        let log: serde_json::Value = serde_json::from_str(&render(
+            ReportFormat::Sarif,
+            &outdated_deps,
+            &outdated_deps,
+            3,
+        ))
+        .unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0]["ruleId"], "outdated-dependency");
+        assert_eq!(results[0]["level"], "note");
+        assert_eq!(results[0]["message"]["text"], "log 0.4.0 -> 0.4.1");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            serde_json::json!({
+                "artifactLocation": { "uri": "Cargo.toml" },
+                "region": { "startLine": 2 },
+            })
+        );
+
+        assert_eq!(results[1]["ruleId"], "breaking-update");
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"],
+            serde_json::json!({
+                "artifactLocation": { "uri": "crates/foo/Cargo.toml" },
+                "region": { "startLine": 5 },
+            })
+        );
+
+        assert_eq!(results[2]["ruleId"], "yanked-version");
+        assert_eq!(results[2]["level"], "error");
+        assert_eq!(
+            results[2]["message"]["text"],
+            "serde 1.0.0 -> 1.0.0, current version yanked"
+        );
+    }
+
+    #[test]
+    fn test_render_github() {
+        let mut breaking = dependency("rand", "0.8.0", "0.9.0");
+        breaking.workspace_path = Some("crates/foo".to_string());
+        let mut held_back = dependency("log", "0.4.0", "0.4.1");
+        held_back.held_back = Some("ignored, for now".to_string());
+        let outdated_deps = Dependencies::new(
+            vec![dependency("serde", "1.0.1", "1.0.200"), breaking, held_back],
+            HashMap::from([(
+                ".".to_string(),
+                "[dependencies]\nserde = \"1.0\"\n".parse().unwrap(),
+            )]),
+        );
+
+        assert_eq!(
+            render(ReportFormat::Github, &outdated_deps, &outdated_deps, 3),
+            [
+                "::warning file=Cargo.toml,line=2::serde 1.0.1 -> 1.0.200",
+                "::warning file=crates/foo/Cargo.toml::rand 0.8.0 -> 0.9.0 (breaking)",
+                "::notice file=Cargo.toml::log 0.4.0 -> 0.4.1, held back: ignored, for now",
+            ]
+            .join("\n")
+        );
+        assert_eq!(escape_github_property("a:b,c%\n"), "a%3Ab%2Cc%25%0A");
+    }
+
+    #[test]
+    fn test_render_summary() {
+        let mut held_back = dependency("rand", "0.8.0", "0.9.0");
+        held_back.held_back = Some("ignored".to_string());
+        held_back.latest_version_date = Some("2024-01-01T00:00:00Z".to_string());
+        let dependencies = Dependencies::new(
+            vec![dependency("serde_json", "1.0.0", "1.0.10"), held_back],
+            HashMap::new(),
+        );
+
+        assert_eq!(
+            render(ReportFormat::Summary, &dependencies, &dependencies, 4),
+            [
+                "2 out of the 4 direct dependencies are outdated.",
+                "",
+                "Name        Kind       Current  Latest  Released    Notes",
+                "----        ----       -------  ------  --------    -----",
+                "serde_json  normal     1.0.0    1.0.10  -",
+                "rand        normal     0.8.0    0.9.0   2024-01-01  held back: ignored",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_porcelain() {
+        let mut held_back = dependency("rand", "0.8.0", "0.9.0");
+        held_back.held_back = Some("ignored".to_string());
+        held_back.package_name = Some("foo".to_string());
+        held_back.workspace_path = Some("crates/foo".to_string());
+        let dependencies = Dependencies::new(
+            vec![dependency("serde", "1.0.0", "1.0.1"), held_back],
+            HashMap::new(),
+        );
+
+        assert_eq!(
+            render(ReportFormat::Porcelain, &dependencies, &dependencies, 4),
+            [
+                "porcelain\tv1",
+                "total\t4\t2",
+                "dependency\tserde\tnormal\t-\t./Cargo.toml\t1.0.0\t1.0.1\tpatch\t-",
+                "dependency\trand\tnormal\tfoo\t./crates/foo/Cargo.toml\t0.8.0\t0.9.0\tminor\theld-back",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_check_summary_and_exit_code() {
+        let up_to_date = Dependencies::new(vec![], HashMap::new());
+        assert_eq!(get_check_exit_code(&up_to_date), 0);
+        assert_eq!(
+            render_check_summary(&up_to_date, 2),
+            "All 2 direct dependencies are up to date!\n"
+        );
+
+        let compatible = Dependencies::new(
+            vec![
+                dependency("serde", "1.0.0", "1.2.0"),
+                dependency("log", "0.4.0", "0.4.1"),
+            ],
+            HashMap::new(),
+        );
+        assert_eq!(get_check_exit_code(&compatible), 2);
+
+        let mut held_back = dependency("rand", "0.8.0", "0.9.0");
+        held_back.held_back = Some("ignored".to_string());
+        let breaking = Dependencies::new(
+            vec![
+                dependency("serde", "1.0.0", "1.2.0"),
+                dependency("toml", "0.7.0", "0.8.0"),
+                held_back,
+            ],
+            HashMap::new(),
+        );
+        assert_eq!(get_check_exit_code(&breaking), 3);
+        assert_eq!(FailOn::Breaking.get_exit_code(&compatible), 0);
+        assert_eq!(FailOn::Breaking.get_exit_code(&breaking), 3);
+        assert_eq!(FailOn::None.get_exit_code(&breaking), 0);
+        assert_eq!(FailOn::Vulnerable.get_exit_code(&breaking), 0);
+
+        let mut yanked = dependency("serde", "1.0.0", "1.0.0");
+        yanked.versions = vec![RegistryVersion {
+            num: "1.0.0".to_string(),
+            yanked: true,
+            ..Default::default()
+        }];
+        let vulnerable = Dependencies::new(vec![yanked], HashMap::new());
+        assert_eq!(FailOn::Vulnerable.get_exit_code(&vulnerable), 4);
+        assert_eq!(FailOn::Outdated.get_exit_code(&vulnerable), 2);
+        assert_eq!(
+            render_check_summary(&breaking, 5),
+            "2 out of the 5 direct dependencies are outdated (1 compatible, 1 breaking).\n  serde 1.0.0 -> 1.2.0\n  toml 0.7.0 -> 0.8.0 (breaking)\n1 held back by policy.\n"
+        );
+
+        let mut unknown = dependency("internal", "1.0.0", "1.0.0");
+        unknown.unknown_source = Some("not found on crates.io".to_string());
+        unknown.held_back = Some("unknown source, not found on crates.io".to_string());
+        let with_unknown = Dependencies::new(
+            vec![dependency("serde", "1.0.0", "1.2.0"), unknown],
+            HashMap::new(),
+        );
+        assert_eq!(get_check_exit_code(&with_unknown), 2);
+        assert_eq!(
+            render_check_summary(&with_unknown, 2),
+            "1 out of the 2 direct dependencies are outdated (1 compatible, 0 breaking).\n  serde 1.0.0 -> 1.2.0\n1 from an unknown source.\n"
+        );
+        assert_eq!(
+            render_check_summary(
+                &Dependencies::new(vec![with_unknown.dependencies[1].clone()], HashMap::new()),
+                2
+            ),
+            "1 of the 2 direct dependencies are up to date!\n1 from an unknown source.\n"
+        );
+        assert_eq!(
+            get_dependency_json(&with_unknown.dependencies[1])["unknown_source"],
+            true
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut yanked = dependency("serde", "1.0.0", "1.1.0");
+        yanked.repository = Some("https://github.com/serde-rs/serde".to_string());
+        yanked.latest_version_date = Some("2024-01-01T00:00:00Z".to_string());
+        yanked.package_name = Some("foo".to_string());
+        yanked.versions = vec![RegistryVersion {
+            num: "1.0.0".to_string(),
+            yanked: true,
+            ..Default::default()
+        }];
+        let mut held_back = dependency("rand", "0.8.0", "0.9.0");
+        held_back.held_back = Some("ignored".to_string());
+
+        let dependencies = Dependencies::new(vec![yanked, held_back], HashMap::new());
+
+        assert_eq!(
+            render(ReportFormat::Markdown, &dependencies, &dependencies, 4),
+            [
+                "2 out of the 4 direct dependencies are outdated.",
+                "",
+                "| Crate | Kind | Member | Current | Latest | Released | Links | Notes |",
+                "| --- | --- | --- | --- | --- | --- | --- | --- |",
+                "| [serde](https://crates.io/crates/serde) | normal | foo | 1.0.0 | 1.1.0 | 2024-01-01 | [repository](https://github.com/serde-rs/serde) [changelog](https://github.com/serde-rs/serde/releases) | current version yanked |",
+                "| [rand](https://crates.io/crates/rand) | normal | - | 0.8.0 | 0.9.0 | - |  | held back: ignored |",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render(
+                ReportFormat::Markdown,
+                &Dependencies::new(vec![], HashMap::new()),
+                &Dependencies::new(vec![], HashMap::new()),
+                4
+            ),
+            "All 4 direct dependencies are up to date!"
+        );
+    }
+}
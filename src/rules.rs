@@ -0,0 +1,261 @@
+use toml_edit::Item;
+
+use crate::dependency::{Dependency, DependencyKind, UpdateMagnitude};
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+// Matches dependencies by crate name, kind and member, where empty lists match everything
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rule {
+    pub crates: Vec<glob::Pattern>,
+    pub kinds: Vec<DependencyKind>,
+    pub members: Vec<glob::Pattern>,
+    pub group: Option<String>,
+    pub allow: Option<Vec<UpdateMagnitude>>,
+    pub schedule: Option<Vec<String>>,
+    pub select: Option<bool>,
+}
+
+impl Rule {
+    pub fn from_item(item: &Item, source: &str) -> Result<Self, String> {
+        let table = item
+            .as_table_like()
+            .ok_or_else(|| format!("Rules in {source} must be tables"))?;
+        let mut rule = Rule::default();
+
+        for (key, value) in table.iter() {
+            let invalid = || format!("Invalid value for `{key}` in a rule of {source}");
+            match key {
+                "crates" => rule.crates = get_patterns(value).ok_or_else(invalid)?,
+                "members" => rule.members = get_patterns(value).ok_or_else(invalid)?,
+                "kinds" => {
+                    rule.kinds = get_strings(value)
+                        .and_then(|kinds| {
+                            kinds
+                                .iter()
+                                .map(|kind| {
+                                    DependencyKind::ordered()
+                                        .into_iter()
+                                        .find(|k| k.name() == kind)
+                                })
+                                .collect()
+                        })
+                        .ok_or_else(invalid)?
+                }
+                "group" => rule.group = Some(value.as_str().ok_or_else(invalid)?.to_string()),
+                "allow" => {
+                    rule.allow = Some(
+                        get_strings(value)
+                            .and_then(|magnitudes| {
+                                magnitudes
+                                    .iter()
+                                    .map(|magnitude| {
+                                        [
+                                            UpdateMagnitude::Major,
+                                            UpdateMagnitude::Minor,
+                                            UpdateMagnitude::Patch,
+                                        ]
+                                        .into_iter()
+                                        .find(|m| m.name() == magnitude)
+                                    })
+                                    .collect()
+                            })
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                "schedule" => {
+                    rule.schedule = Some(
+                        get_strings(value)
+                            .filter(|days| days.iter().all(|day| WEEKDAYS.contains(&day.as_str())))
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                "select" => rule.select = Some(value.as_bool().ok_or_else(invalid)?),
+                _ => return Err(format!("Unknown key `{key}` in a rule of {source}")),
+            }
+        }
+
+        Ok(rule)
+    }
+
+    pub fn get_held_back_reason(&self, dependency: &Dependency, today: i64) -> Option<String> {
+        if let (Some(allow), Some(magnitude)) = (&self.allow, dependency.magnitude()) {
+            if !allow.contains(&magnitude) {
+                return Some(format!("{} update not allowed by rule", magnitude.name()));
+            }
+        }
+
+        if let Some(schedule) = &self.schedule {
+            let weekday = WEEKDAYS[(today + 3).rem_euclid(7) as usize];
+            if !schedule.iter().any(|day| day == weekday) {
+                return Some(format!("only updated on {}", schedule.join(", ")));
+            }
+        }
+
+        None
+    }
+
+    fn matches(&self, dependency: &Dependency) -> bool {
+        let member = dependency
+            .package_name
+            .as_deref()
+            .filter(|name| !name.is_empty());
+        let workspace_path = dependency.workspace_path.as_deref().unwrap_or(".");
+
+        (self.crates.is_empty()
+            || self
+                .crates
+                .iter()
+                .any(|pattern| pattern.matches(&dependency.name)))
+            && (self.kinds.is_empty() || self.kinds.contains(&dependency.kind))
+            && (self.members.is_empty()
+                || self.members.iter().any(|pattern| {
+                    member.is_some_and(|member| pattern.matches(member))
+                        || pattern.matches(workspace_path)
+                }))
+    }
+}
+
+// The settings of every matching rule, where later rules override the earlier ones
+pub fn resolve(rules: &[Rule], dependency: &Dependency) -> Rule {
+    let mut resolved = Rule::default();
+
+    for rule in rules.iter().filter(|rule| rule.matches(dependency)) {
+        resolved.group = rule.group.clone().or(resolved.group);
+        resolved.allow = rule.allow.clone().or(resolved.allow);
+        resolved.schedule = rule.schedule.clone().or(resolved.schedule);
+        resolved.select = rule.select.or(resolved.select);
+    }
+
+    resolved
+}
+
+fn get_strings(item: &Item) -> Option<Vec<String>> {
+    if let Some(value) = item.as_str() {
+        return Some(vec![value.to_string()]);
+    }
+
+    item.as_array()?
+        .iter()
+        .map(|value| value.as_str().map(str::to_string))
+        .collect()
+}
+
+fn get_patterns(item: &Item) -> Option<Vec<glob::Pattern>> {
+    get_strings(item)?
+        .iter()
+        .map(|pattern| glob::Pattern::new(pattern).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_rules(content: &str) -> Result<Vec<Rule>, String> {
+        let document = content.parse::<toml_edit::DocumentMut>().unwrap();
+        document["rules"]
+            .as_array_of_tables()
+            .unwrap()
+            .iter()
+            .map(|table| Rule::from_item(&Item::Table(table.clone()), "config.toml"))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let rules = get_rules(
+            r#"
+            [[rules]]
+            crates = ["tokio*", "hyper"]
+            group = "async"
+            allow = ["minor", "patch"]
+            select = true
+
+            [[rules]]
+            crates = "tokio-util"
+            kinds = ["dev"]
+            members = "crates/*"
+            schedule = ["monday"]
+            select = false
+            "#,
+        )
+        .unwrap();
+
+        let tokio = Dependency {
+            name: "tokio".to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "2.0.0".to_string(),
+            ..Default::default()
+        };
+        let resolved = resolve(&rules, &tokio);
+        assert_eq!(resolved.group.as_deref(), Some("async"));
+        assert_eq!(resolved.select, Some(true));
+        assert_eq!(
+            resolved.get_held_back_reason(&tokio, 0),
+            Some("major update not allowed by rule".to_string())
+        );
+
+        let tokio_util = Dependency {
+            name: "tokio-util".to_string(),
+            kind: DependencyKind::Dev,
+            workspace_path: Some("crates/foo".to_string()),
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.1.0".to_string(),
+            ..Default::default()
+        };
+        let resolved = resolve(&rules, &tokio_util);
+        assert_eq!(resolved.group.as_deref(), Some("async"));
+        assert_eq!(resolved.select, Some(false));
+        // 1970-01-01 was a Thursday, followed by Monday the 5th
+        assert_eq!(
+            resolved.get_held_back_reason(&tokio_util, 0),
+            Some("only updated on monday".to_string())
+        );
+        assert_eq!(resolved.get_held_back_reason(&tokio_util, 4), None);
+
+        let resolved = resolve(
+            &rules,
+            &Dependency {
+                workspace_path: None,
+                ..tokio_util.clone()
+            },
+        );
+        assert_eq!(resolved.select, Some(true));
+
+        assert_eq!(
+            resolve(
+                &rules,
+                &Dependency {
+                    name: "serde".to_string(),
+                    ..Default::default()
+                }
+            ),
+            Rule::default()
+        );
+    }
+
+    #[test]
+    fn test_from_item_errors() {
+        assert_eq!(
+            get_rules("[[rules]]\nunknown = true"),
+            Err("Unknown key `unknown` in a rule of config.toml".to_string())
+        );
+        assert_eq!(
+            get_rules("[[rules]]\nallow = [\"huge\"]"),
+            Err("Invalid value for `allow` in a rule of config.toml".to_string())
+        );
+        assert_eq!(
+            get_rules("[[rules]]\nschedule = \"someday\""),
+            Err("Invalid value for `schedule` in a rule of config.toml".to_string())
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use semver::Version;
+use std::collections::HashMap;
+
+use crate::{
+    api::{self, Advisory},
+    dependency::{Dependencies, Dependency, DependencyKind},
+    error::Error,
+    logger, pool,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn from_score(score: f64) -> Self {
+        if score >= 9.0 {
+            Severity::Critical
+        } else if score >= 7.0 {
+            Severity::High
+        } else if score >= 4.0 {
+            Severity::Medium
+        } else if score > 0.0 {
+            Severity::Low
+        } else {
+            Severity::None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::None => "none",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+// Keeps the advisories of the current versions fixed by updating to the latest ones, the others
+// are only reported since updating doesn't help
+// The advisories of a crate from an unknown source would be those of its crates.io namesake
+fn is_audited(dependency: &Dependency) -> bool {
+    dependency.kind != DependencyKind::Toolchain && dependency.unknown_source.is_none()
+}
+
+pub fn annotate(dependencies: &mut Dependencies) -> Result<(), Error> {
+    let queries = dependencies
+        .iter()
+        .filter(|dependency| is_audited(dependency))
+        .map(|dependency| {
+            (
+                dependency.crate_name().to_string(),
+                dependency.current_version.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut advisories = vec![];
+    for result in pool::map(queries, |(crate_name, version)| {
+        api::get_advisories(&crate_name, &version).map_err(|e| Error::Network {
+            crate_name,
+            message: e.to_string(),
+        })
+    }) {
+        advisories.push(result?);
+    }
+
+    for (dependency, advisories) in dependencies
+        .dependencies
+        .iter_mut()
+        .filter(|dependency| is_audited(dependency))
+        .zip(advisories)
+    {
+        let (fixed, unfixed) = deduplicate(advisories)
+            .into_iter()
+            .partition::<Vec<_>, _>(|advisory| is_fixed_by_update(advisory, dependency));
+
+        for advisory in unfixed {
+            logger::warn(format!(
+                "{} {} is affected by {}, which updating to {} doesn't fix",
+                dependency.name, dependency.current_version, advisory.id, dependency.latest_version
+            ));
+        }
+        dependency.advisories = fixed;
+    }
+
+    Ok(())
+}
+
+// The same vulnerability is often published both by RustSec and GitHub, referencing each other
+fn deduplicate(advisories: Vec<Advisory>) -> Vec<Advisory> {
+    let mut unique: Vec<Advisory> = vec![];
+
+    for advisory in advisories {
+        let is_duplicate = unique.iter().any(|other| {
+            other.aliases.contains(&advisory.id) || advisory.aliases.contains(&other.id)
+        });
+        if !is_duplicate {
+            unique.push(advisory);
+        }
+    }
+
+    unique
+}
+
+fn is_fixed_by_update(advisory: &Advisory, dependency: &Dependency) -> bool {
+    let (Ok(current_version), Ok(latest_version)) = (
+        Version::parse(&dependency.current_version),
+        Version::parse(&dependency.latest_version),
+    ) else {
+        return false;
+    };
+
+    advisory
+        .fixed_versions
+        .iter()
+        .filter_map(|fixed| Version::parse(fixed).ok())
+        .any(|fixed| current_version < fixed && fixed <= latest_version)
+}
+
+// Advisories without a CVSS vector rank above the dependencies without any advisory
+pub fn get_severity(dependency: &Dependency) -> Option<Severity> {
+    dependency
+        .advisories
+        .iter()
+        .map(|advisory| {
+            advisory
+                .cvss
+                .as_deref()
+                .and_then(get_base_score)
+                .map_or(Severity::None, Severity::from_score)
+        })
+        .max()
+}
+
+// Base score of a CVSS v3 vector, following the formulas of the specification
+fn get_base_score(vector: &str) -> Option<f64> {
+    let metrics = vector
+        .split('/')
+        .skip(1)
+        .filter_map(|metric| metric.split_once(':'))
+        .collect::<HashMap<_, _>>();
+
+    let scope_changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let attack_vector = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges_required = match (*metrics.get("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let user_interaction = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact = |metric| match *metrics.get(metric)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+
+    let impact_subscore: f64 =
+        1.0 - (1.0 - impact("C")?) * (1.0 - impact("I")?) * (1.0 - impact("A")?);
+    let impact = if scope_changed {
+        7.52 * (impact_subscore - 0.029) - 3.25 * (impact_subscore - 0.02).powi(15)
+    } else {
+        6.42 * impact_subscore
+    };
+    let exploitability =
+        8.22 * attack_vector * attack_complexity * privileges_required * user_interaction;
+
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let score = if scope_changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(score.min(10.0)))
+}
+
+// Rounds up to one decimal, working on integers to avoid floating point errors like the
+// specification recommends
+fn round_up(value: f64) -> f64 {
+    let integer = (value * 100_000.0).round() as u64;
+
+    if integer.is_multiple_of(10_000) {
+        integer as f64 / 100_000.0
+    } else {
+        (integer / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_base_score() {
+        assert_eq!(
+            get_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            get_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"),
+            Some(10.0)
+        );
+        assert_eq!(
+            get_base_score("CVSS:3.0/AV:N/AC:L/PR:N/UI:R/S:U/C:L/I:N/A:N"),
+            Some(4.3)
+        );
+        assert_eq!(
+            get_base_score("CVSS:3.1/AV:L/AC:H/PR:H/UI:R/S:U/C:N/I:N/A:N"),
+            Some(0.0)
+        );
+        assert_eq!(get_base_score("CVSS:3.1/AV:N"), None);
+    }
+
+    #[test]
+    fn test_advisories() {
+        let advisory = |id: &str, aliases: &[&str], cvss: Option<&str>, fixed: &str| Advisory {
+            id: id.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            cvss: cvss.map(str::to_string),
+            fixed_versions: vec![fixed.to_string()],
+            ..Default::default()
+        };
+        let mut dependency = Dependency {
+            name: "foo".to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: "1.2.0".to_string(),
+            ..Default::default()
+        };
+
+        let advisories = deduplicate(vec![
+            advisory("RUSTSEC-2024-0001", &["GHSA-1"], None, "1.1.0"),
+            advisory("GHSA-1", &["RUSTSEC-2024-0001"], None, "1.1.0"),
+            advisory("RUSTSEC-2024-0002", &[], None, "1.3.0"),
+        ]);
+        assert_eq!(advisories.len(), 2);
+        assert!(is_fixed_by_update(&advisories[0], &dependency));
+        assert!(!is_fixed_by_update(&advisories[1], &dependency));
+
+        assert_eq!(get_severity(&dependency), None);
+        dependency.advisories = advisories;
+        assert_eq!(get_severity(&dependency), Some(Severity::None));
+        dependency.advisories[1].cvss =
+            Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string());
+        assert_eq!(get_severity(&dependency), Some(Severity::Critical));
+    }
+}
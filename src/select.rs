@@ -0,0 +1,367 @@
+use crate::{dependency::Dependency, security};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Attribute {
+    Name,
+    Member,
+    Kind,
+    Magnitude,
+    Severity,
+    Yanked,
+    Breaking,
+    Prerelease,
+    Unused,
+}
+
+impl Attribute {
+    const ALL: [Attribute; 9] = [
+        Attribute::Name,
+        Attribute::Member,
+        Attribute::Kind,
+        Attribute::Magnitude,
+        Attribute::Severity,
+        Attribute::Yanked,
+        Attribute::Breaking,
+        Attribute::Prerelease,
+        Attribute::Unused,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Attribute::Name => "name",
+            Attribute::Member => "member",
+            Attribute::Kind => "kind",
+            Attribute::Magnitude => "magnitude",
+            Attribute::Severity => "severity",
+            Attribute::Yanked => "yanked",
+            Attribute::Breaking => "breaking",
+            Attribute::Prerelease => "prerelease",
+            Attribute::Unused => "unused",
+        }
+    }
+
+    // Names and members are matched with globs, the other attributes have a fixed set of values
+    fn get_allowed_values(self) -> Option<&'static [&'static str]> {
+        match self {
+            Attribute::Name | Attribute::Member => None,
+            Attribute::Kind => Some(&["normal", "dev", "build", "workspace", "toolchain"]),
+            Attribute::Magnitude => Some(&["major", "minor", "patch"]),
+            Attribute::Severity => Some(&["none", "low", "medium", "high", "critical"]),
+            Attribute::Yanked | Attribute::Breaking | Attribute::Prerelease | Attribute::Unused => {
+                Some(&["true", "false"])
+            }
+        }
+    }
+
+    fn get_values(self, dependency: &Dependency) -> Vec<String> {
+        match self {
+            Attribute::Name => vec![dependency.name.clone()],
+            Attribute::Member => dependency
+                .package_name
+                .iter()
+                .filter(|name| !name.is_empty())
+                .cloned()
+                .chain([dependency
+                    .workspace_path
+                    .clone()
+                    .unwrap_or_else(|| ".".to_string())])
+                .collect(),
+            Attribute::Kind => vec![dependency.kind.name().to_string()],
+            Attribute::Magnitude => dependency
+                .magnitude()
+                .map(|magnitude| magnitude.name().to_string())
+                .into_iter()
+                .collect(),
+            Attribute::Severity => security::get_severity(dependency)
+                .map(|severity| severity.name().to_string())
+                .into_iter()
+                .collect(),
+            Attribute::Yanked => vec![dependency.is_current_version_yanked().to_string()],
+            Attribute::Breaking => vec![dependency.is_breaking_update().to_string()],
+            Attribute::Prerelease => vec![dependency.is_prerelease_update().to_string()],
+            Attribute::Unused => vec![dependency.possibly_unused.to_string()],
+        }
+    }
+}
+
+// Boolean expression over the attributes of a dependency, e.g. `kind==dev || magnitude==patch`
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expression {
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare {
+        attribute: Attribute,
+        equal: bool,
+        pattern: glob::Pattern,
+    },
+}
+
+impl Expression {
+    pub fn matches(&self, dependency: &Dependency) -> bool {
+        match self {
+            Expression::Or(left, right) => left.matches(dependency) || right.matches(dependency),
+            Expression::And(left, right) => left.matches(dependency) && right.matches(dependency),
+            Expression::Not(expression) => !expression.matches(dependency),
+            Expression::Compare {
+                attribute,
+                equal,
+                pattern,
+            } => {
+                attribute
+                    .get_values(dependency)
+                    .iter()
+                    .any(|value| pattern.matches(value))
+                    == *equal
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    Open,
+    Close,
+    Not,
+    And,
+    Or,
+    Equal,
+    NotEqual,
+    Word(String),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Open => "`(`".to_string(),
+            Token::Close => "`)`".to_string(),
+            Token::Not => "`!`".to_string(),
+            Token::And => "`&&`".to_string(),
+            Token::Or => "`||`".to_string(),
+            Token::Equal => "`==`".to_string(),
+            Token::NotEqual => "`!=`".to_string(),
+            Token::Word(word) => format!("`{word}`"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::NotEqual,
+            '!' => Token::Not,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Equal,
+            '"' | '\'' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Missing closing {c} in `{input}`")),
+                    }
+                }
+                Token::Word(word)
+            }
+            c if !is_operator_char(c) => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !is_operator_char(*c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            c => return Err(format!("Unexpected `{c}` in `{input}`")),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn is_operator_char(c: char) -> bool {
+    "()!&|=\"'".contains(c)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, token: Token) -> bool {
+        let matches = self.tokens.get(self.position) == Some(&token);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+        while self.next_if(Token::Or) {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_unary()?;
+        while self.next_if(Token::And) {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expression::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    Some(token) => Err(format!("Expected `)` but found {}", token.describe())),
+                    None => Err("Missing closing `)`".to_string()),
+                }
+            }
+            Some(Token::Word(attribute)) => self.parse_comparison(&attribute),
+            Some(token) => Err(format!("Unexpected {}", token.describe())),
+            None => Err("Unexpected end of the expression".to_string()),
+        }
+    }
+
+    fn parse_comparison(&mut self, attribute: &str) -> Result<Expression, String> {
+        let attribute = Attribute::ALL
+            .into_iter()
+            .find(|a| a.name() == attribute)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown attribute `{attribute}`, expected one of {}",
+                    Attribute::ALL.map(Attribute::name).join(", ")
+                )
+            })?;
+
+        let equal = match self.next() {
+            Some(Token::Equal) => true,
+            Some(Token::NotEqual) => false,
+            _ => {
+                return Err(format!(
+                    "Expected `==` or `!=` after `{}`",
+                    attribute.name()
+                ))
+            }
+        };
+        let Some(Token::Word(value)) = self.next() else {
+            return Err(format!(
+                "Expected a value to compare `{}` to",
+                attribute.name()
+            ));
+        };
+
+        if let Some(allowed_values) = attribute.get_allowed_values() {
+            if !allowed_values.contains(&value.as_str()) {
+                return Err(format!(
+                    "Invalid value `{value}` for `{}`, expected one of {}",
+                    attribute.name(),
+                    allowed_values.join(", ")
+                ));
+            }
+        }
+        let pattern =
+            glob::Pattern::new(&value).map_err(|e| format!("Invalid pattern `{value}`: {e}"))?;
+
+        Ok(Expression::Compare {
+            attribute,
+            equal,
+            pattern,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expression, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+
+    let expression = parser.parse_or()?;
+    match parser.next() {
+        None => Ok(expression),
+        Some(token) => Err(format!("Unexpected {}", token.describe())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::DependencyKind;
+
+    #[test]
+    fn test_matches() {
+        let dependency = |name: &str, kind, latest_version: &str| Dependency {
+            name: name.to_string(),
+            kind,
+            current_version: "1.0.0".to_string(),
+            latest_version: latest_version.to_string(),
+            workspace_path: Some("crates/foo".to_string()),
+            package_name: Some("foo".to_string()),
+            ..Default::default()
+        };
+        let serde = dependency("serde", DependencyKind::Normal, "1.0.1");
+        let tokio = dependency("tokio", DependencyKind::Dev, "2.0.0");
+        let matches = |input: &str| {
+            let expression = parse(input).unwrap();
+            (expression.matches(&serde), expression.matches(&tokio))
+        };
+
+        assert_eq!(matches("kind==dev || magnitude==patch"), (true, true));
+        assert_eq!(matches("kind == dev && magnitude == patch"), (false, false));
+        assert_eq!(matches("!(kind==dev)"), (true, false));
+        assert_eq!(matches("name != 'tok*'"), (true, false));
+        assert_eq!(matches("breaking==true"), (false, true));
+        assert_eq!(matches("member==foo && member==crates/*"), (true, true));
+        assert_eq!(matches("yanked==true || severity==high"), (false, false));
+        assert_eq!(
+            matches("kind==normal || kind==dev && magnitude==patch"),
+            (true, false)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("type==dev").unwrap_err(),
+            "Unknown attribute `type`, expected one of name, member, kind, magnitude, severity, yanked, breaking, prerelease, unused"
+        );
+        assert_eq!(
+            parse("kind==devv").unwrap_err(),
+            "Invalid value `devv` for `kind`, expected one of normal, dev, build, workspace, toolchain"
+        );
+        assert_eq!(
+            parse("kind=dev").unwrap_err(),
+            "Unexpected `=` in `kind=dev`"
+        );
+        assert_eq!(parse("(kind==dev").unwrap_err(), "Missing closing `)`");
+        assert_eq!(
+            parse("kind==dev &&").unwrap_err(),
+            "Unexpected end of the expression"
+        );
+        assert_eq!(parse("kind==dev)").unwrap_err(), "Unexpected `)`");
+        assert_eq!(
+            parse("name").unwrap_err(),
+            "Expected `==` or `!=` after `name`"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use crate::{
+    args::Args,
+    dependency::{Dependencies, DependencyKind},
+    error::Error,
+    logger,
+};
+
+// Marks the dependencies whose items are part of the public API of the package using them, a
+// breaking update of those is likely a breaking change of the package as well
+pub fn annotate(dependencies: &mut Dependencies) {
+    let reexported_crates = dependencies
+        .cargo_toml_files
+        .keys()
+        .map(|workspace_path| {
+            (
+                workspace_path.clone(),
+                get_package_reexported_crates(Path::new(workspace_path)),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    for dependency in dependencies.dependencies.iter_mut() {
+        let name = dependency.name.replace('-', "_");
+        dependency.reexported = match dependency.kind {
+            DependencyKind::Normal => reexported_crates
+                .get(dependency.workspace_path.as_deref().unwrap_or("."))
+                .is_some_and(|crates| crates.contains(&name)),
+            DependencyKind::Workspace => reexported_crates
+                .values()
+                .any(|crates| crates.contains(&name)),
+            DependencyKind::Dev | DependencyKind::Build | DependencyKind::Toolchain => false,
+        };
+    }
+}
+
+fn get_package_reexported_crates(package_path: &Path) -> HashSet<String> {
+    let mut crates = HashSet::new();
+    let mut dirs = vec![package_path.join("src")];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                if let Ok(source) = std::fs::read_to_string(&path) {
+                    crates.extend(get_reexported_crates(&source));
+                }
+            }
+        }
+    }
+
+    crates
+}
+
+fn get_reexported_crates(source: &str) -> HashSet<String> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let path = line
+                .strip_prefix("pub use ")
+                .or_else(|| line.strip_prefix("pub extern crate "))?;
+            let name = path
+                .trim_start_matches("::")
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()?;
+
+            (!name.is_empty() && !matches!(name, "crate" | "self" | "super"))
+                .then(|| name.to_string())
+        })
+        .collect()
+}
+
+// Compares the public API of the updated package with the one of the last commit
+pub fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let command_line = "cargo semver-checks --baseline-rev HEAD";
+
+    logger::print(format!(
+        "\nExecuting {}...\n",
+        args.theme.emphasis("cargo semver-checks")
+    ));
+    logger::info(format!("Running {command_line}"));
+    let status = std::process::Command::new("cargo")
+        .args(["semver-checks", "--baseline-rev", "HEAD"])
+        .status()?;
+
+    if !status.success() {
+        return Err(Error::Verification {
+            command: command_line.to_string(),
+            status: status.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_reexported_crates() {
+        let source = r#"
+use serde::Serialize;
+pub use semver::{Version, VersionReq};
+pub use ::toml_edit as toml;
+pub(crate) use glob::glob;
+pub use crate::dependency::Dependency;
+pub use self::args::Args;
+    pub extern crate curl;
+"#;
+
+        let mut crates = get_reexported_crates(source)
+            .into_iter()
+            .collect::<Vec<_>>();
+        crates.sort();
+        assert_eq!(crates, vec!["curl", "semver", "toml_edit"]);
+    }
+
+    #[test]
+    fn test_annotate() {
+        let workspace_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/packages/reexports")
+            .to_string_lossy()
+            .to_string();
+        let dependency = |name: &str, kind| crate::dependency::Dependency {
+            name: name.to_string(),
+            kind,
+            workspace_path: Some(workspace_path.clone()),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("serde", DependencyKind::Normal),
+                dependency("serde", DependencyKind::Dev),
+                dependency("toml-edit", DependencyKind::Workspace),
+                dependency("glob", DependencyKind::Normal),
+            ],
+            HashMap::from([(workspace_path.clone(), Default::default())]),
+        );
+
+        annotate(&mut dependencies);
+        assert!(dependencies.dependencies[0].reexported);
+        assert!(!dependencies.dependencies[1].reexported);
+        assert!(dependencies.dependencies[2].reexported);
+        assert!(!dependencies.dependencies[3].reexported);
+    }
+}
@@ -0,0 +1,225 @@
+use crossterm::style::{style, ContentStyle, StyledContent, Stylize};
+use std::{
+    fmt::Display,
+    io::IsTerminal,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::dependency::UpdateMagnitude;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Theme {
+    #[default]
+    Default,
+    HighContrast,
+}
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn is_enabled(self, is_terminal: bool, no_color: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    pub fn apply(self) {
+        let no_color = std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+        let enabled = self.is_enabled(std::io::stdout().is_terminal(), no_color);
+
+        COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+        crossterm::style::force_color_output(enabled);
+    }
+}
+
+fn paint<D: Display>(content: StyledContent<D>) -> StyledContent<D> {
+    paint_with(content, COLOR_ENABLED.load(Ordering::Relaxed))
+}
+
+fn paint_with<D: Display>(mut content: StyledContent<D>, enabled: bool) -> StyledContent<D> {
+    if !enabled {
+        *content.style_mut() = ContentStyle::new();
+    }
+
+    content
+}
+
+impl Theme {
+    pub fn accent<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(match self {
+            Theme::Default => style(content).cyan(),
+            Theme::HighContrast => style(content).cyan().bold(),
+        })
+    }
+
+    pub fn emphasis<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(style(content).bold())
+    }
+
+    pub fn secondary<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(match self {
+            Theme::Default => style(content).italic().dim(),
+            Theme::HighContrast => style(content),
+        })
+    }
+
+    pub fn description<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(match self {
+            Theme::Default => style(content).dim(),
+            Theme::HighContrast => style(content),
+        })
+    }
+
+    pub fn link<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(match self {
+            Theme::Default => style(content).underline_black(),
+            Theme::HighContrast => style(content).underlined(),
+        })
+    }
+
+    pub fn package<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(match self {
+            Theme::Default => style(content).blue().italic(),
+            Theme::HighContrast => style(content).bold(),
+        })
+    }
+
+    pub fn version<D: Display>(
+        self,
+        content: D,
+        magnitude: Option<UpdateMagnitude>,
+        prerelease: bool,
+    ) -> StyledContent<D> {
+        let content = match (prerelease, magnitude) {
+            (true, _) => style(content).magenta(),
+            (false, Some(UpdateMagnitude::Major)) => style(content).red(),
+            (false, Some(UpdateMagnitude::Minor)) => style(content).yellow(),
+            (false, Some(UpdateMagnitude::Patch)) => style(content).green(),
+            (false, None) => style(content),
+        };
+
+        paint(match self {
+            Theme::Default => content,
+            Theme::HighContrast => content.bold(),
+        })
+    }
+
+    pub fn warning<D: Display>(self, content: D) -> StyledContent<D> {
+        paint(style(content).red().bold())
+    }
+
+    pub fn held_back<D: Display>(self, content: D, highlighted: bool) -> StyledContent<D> {
+        paint(match (self, highlighted) {
+            (Theme::Default, true) => style(content).green().dim(),
+            (Theme::Default, false) => style(content).dim(),
+            (Theme::HighContrast, true) => style(content).reverse().italic(),
+            (Theme::HighContrast, false) => style(content).italic(),
+        })
+    }
+
+    pub fn row<D: Display>(self, content: D, highlighted: bool) -> StyledContent<D> {
+        paint(match (self, highlighted) {
+            (Theme::Default, true) => style(content).green(),
+            (Theme::Default, false) => style(content).black(),
+            (Theme::HighContrast, true) => style(content).reverse().bold(),
+            (Theme::HighContrast, false) => style(content),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::style::{Attribute, Color};
+
+    #[test]
+    fn test_high_contrast_never_dims() {
+        let theme = Theme::HighContrast;
+
+        for styled in [
+            theme.accent("text"),
+            theme.emphasis("text"),
+            theme.secondary("text"),
+            theme.description("text"),
+            theme.link("text"),
+            theme.package("text"),
+            theme.version("text", Some(UpdateMagnitude::Major), false),
+            theme.warning("text"),
+            theme.held_back("text", true),
+            theme.held_back("text", false),
+            theme.row("text", true),
+            theme.row("text", false),
+        ] {
+            assert!(!styled.style().attributes.has(Attribute::Dim));
+            assert_ne!(styled.style().foreground_color, Some(Color::Black));
+        }
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert!(ColorChoice::Auto.is_enabled(true, false));
+        assert!(!ColorChoice::Auto.is_enabled(false, false));
+        assert!(!ColorChoice::Auto.is_enabled(true, true));
+        assert!(ColorChoice::Always.is_enabled(false, true));
+        assert!(!ColorChoice::Never.is_enabled(true, false));
+
+        let styled = paint_with(style("text").red().bold(), false);
+        assert_eq!(styled.style(), &ContentStyle::new());
+        assert_eq!(styled.to_string(), "text");
+    }
+
+    #[test]
+    fn test_default_theme_row() {
+        assert_eq!(
+            Theme::Default.row("text", true).style().foreground_color,
+            Some(Color::Green)
+        );
+        assert_eq!(
+            Theme::Default.row("text", false).style().foreground_color,
+            Some(Color::Black)
+        );
+    }
+
+    #[test]
+    fn test_version_colors() {
+        let theme = Theme::Default;
+        assert_eq!(
+            theme
+                .version("text", Some(UpdateMagnitude::Major), false)
+                .style()
+                .foreground_color,
+            Some(Color::Red)
+        );
+        assert_eq!(
+            theme
+                .version("text", Some(UpdateMagnitude::Minor), false)
+                .style()
+                .foreground_color,
+            Some(Color::Yellow)
+        );
+        assert_eq!(
+            theme
+                .version("text", Some(UpdateMagnitude::Patch), false)
+                .style()
+                .foreground_color,
+            Some(Color::Green)
+        );
+        assert_eq!(
+            theme
+                .version("text", Some(UpdateMagnitude::Patch), true)
+                .style()
+                .foreground_color,
+            Some(Color::Magenta)
+        );
+    }
+}
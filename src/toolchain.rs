@@ -0,0 +1,136 @@
+use semver::Version;
+use toml_edit::{DocumentMut, Value};
+
+use crate::{
+    api,
+    dependency::{write_atomically, Dependency, DependencyKind},
+    diff,
+    error::Error,
+};
+
+pub const TOOLCHAIN_FILE: &str = "rust-toolchain.toml";
+
+// Only a toolchain pinned to a stable version can be bumped, unlike channels such as `stable` or
+// `nightly` which already follow the latest release
+pub fn get_dependency() -> Result<Option<Dependency>, Error> {
+    let Some(current_version) = std::fs::read_to_string(TOOLCHAIN_FILE)
+        .ok()
+        .and_then(|content| get_pinned_version(&content))
+    else {
+        return Ok(None);
+    };
+
+    let (latest_version, latest_version_date) =
+        api::get_latest_rust_release().map_err(|e| Error::Network {
+            crate_name: "rust".to_string(),
+            message: e.to_string(),
+        })?;
+
+    Ok(Some(Dependency {
+        name: "rust".to_string(),
+        current_version,
+        latest_version,
+        latest_version_date,
+        repository: Some("https://github.com/rust-lang/rust".to_string()),
+        description: Some(format!("Toolchain pinned in {TOOLCHAIN_FILE}")),
+        kind: DependencyKind::Toolchain,
+        ..Default::default()
+    }))
+}
+
+fn get_pinned_version(content: &str) -> Option<String> {
+    let document = content.parse::<DocumentMut>().ok()?;
+    let channel = document.get("toolchain")?.get("channel")?.as_str()?;
+
+    let version = match channel.matches('.').count() {
+        1 => format!("{channel}.0"),
+        2 => channel.to_string(),
+        _ => return None,
+    };
+    Version::parse(&version)
+        .ok()
+        .filter(|version| version.pre.is_empty())
+        .map(|version| version.to_string())
+}
+
+// The channel keeps its precision, `1.80` is bumped to `1.82` rather than `1.82.0`
+fn set_channel(content: &str, version: &str) -> Option<String> {
+    let mut document = content.parse::<DocumentMut>().ok()?;
+    let channel = document
+        .get_mut("toolchain")?
+        .get_mut("channel")?
+        .as_value_mut()?;
+
+    let version = match channel.as_str()?.matches('.').count() {
+        1 => version.rsplit_once('.')?.0,
+        _ => version,
+    };
+    let decor = channel.decor().clone();
+    *channel = Value::from(version);
+    *channel.decor_mut() = decor;
+
+    Some(document.to_string())
+}
+
+fn get_update(dependencies: &[Dependency]) -> Option<(String, String)> {
+    let dependency = dependencies
+        .iter()
+        .find(|dependency| dependency.kind == DependencyKind::Toolchain)?;
+    let content = std::fs::read_to_string(TOOLCHAIN_FILE).ok()?;
+    let updated = set_channel(&content, &dependency.latest_version)?;
+
+    Some((content, updated))
+}
+
+// Returns the previous content of the toolchain file when it was updated, to restore it
+pub fn write(dependencies: &[Dependency]) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some((content, updated)) = get_update(dependencies) else {
+        return Ok(None);
+    };
+
+    write_atomically(std::path::Path::new(TOOLCHAIN_FILE), &updated)?;
+    Ok(Some(content))
+}
+
+pub fn get_diff(dependencies: &[Dependency]) -> String {
+    get_update(dependencies)
+        .map(|(content, updated)| diff::get_unified_diff(TOOLCHAIN_FILE, &content, &updated))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_pinned_version() {
+        let toolchain = |channel: &str| format!("[toolchain]\nchannel = \"{channel}\"\n");
+
+        assert_eq!(
+            get_pinned_version(&toolchain("1.80.1")),
+            Some("1.80.1".to_string())
+        );
+        assert_eq!(
+            get_pinned_version(&toolchain("1.80")),
+            Some("1.80.0".to_string())
+        );
+        assert_eq!(get_pinned_version(&toolchain("stable")), None);
+        assert_eq!(get_pinned_version(&toolchain("nightly-2024-10-01")), None);
+        assert_eq!(get_pinned_version("[toolchain]\n"), None);
+    }
+
+    #[test]
+    fn test_set_channel() {
+        let content = "[toolchain]\nchannel = \"1.80\" # pinned\ncomponents = [\"clippy\"]\n";
+
+        assert_eq!(
+            set_channel(content, "1.82.0"),
+            Some(content.replace("1.80", "1.82"))
+        );
+        assert_eq!(
+            set_channel(&content.replace("1.80", "1.80.1"), "1.82.0"),
+            Some(content.replace("1.80", "1.82.0"))
+        );
+        assert_eq!(set_channel("", "1.82.0"), None);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::logger;
+
+// Runs `cargo tree --invert` for the crate, listing the direct dependencies of the workspace
+// members that pull it in before the full tree, those are the ones holding back its version
+pub fn get_reverse_tree_lines(
+    crate_name: &str,
+    cargo_flags: &[&str],
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut command = vec![
+        "tree",
+        "--invert",
+        crate_name,
+        "--workspace",
+        "--edges",
+        "normal,build,dev",
+        "--prefix",
+        "depth",
+    ];
+    command.extend(
+        cargo_flags
+            .iter()
+            .filter(|flag| matches!(**flag, "--locked" | "--frozen" | "--offline")),
+    );
+    logger::info(format!("Running cargo {}", command.join(" ")));
+
+    let output = std::process::Command::new("cargo")
+        .args(&command)
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "`cargo tree --invert {crate_name}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let tree = parse_tree(&String::from_utf8_lossy(&output.stdout));
+    Ok(render(crate_name, &tree))
+}
+
+fn parse_tree(output: &str) -> Vec<(usize, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let label = line.trim_start_matches(|c: char| c.is_ascii_digit());
+            let depth = line[..line.len() - label.len()].parse().ok()?;
+            Some((depth, label.to_string()))
+        })
+        .collect()
+}
+
+// Local packages are the only ones shown with their path, e.g. `foo v0.1.0 (/path/to/foo)`
+fn is_local_package(label: &str) -> bool {
+    label
+        .rsplit_once(" (")
+        .is_some_and(|(_, source)| source.starts_with('/') || source.get(1..3) == Some(":\\"))
+}
+
+fn get_constraining_dependencies(tree: &[(usize, String)]) -> Vec<String> {
+    let mut constraining = vec![];
+    let mut ancestors: Vec<&str> = vec![];
+
+    for (depth, label) in tree {
+        ancestors.truncate(*depth);
+
+        if is_local_package(label) && *depth > 0 {
+            let member = label.split(' ').next().unwrap_or_default();
+            let dependency = ancestors[depth - 1].trim_end_matches(" (*)");
+            let line = format!("{dependency}, required by {member}");
+            if !constraining.contains(&line) {
+                constraining.push(line);
+            }
+        }
+
+        ancestors.push(label);
+    }
+
+    constraining
+}
+
+fn render(crate_name: &str, tree: &[(usize, String)]) -> Vec<String> {
+    let mut lines = vec![format!("Direct dependencies pulling in {crate_name}:")];
+    lines.extend(
+        get_constraining_dependencies(tree)
+            .into_iter()
+            .map(|dependency| format!("  {dependency}")),
+    );
+    lines.push(String::new());
+    lines.extend(
+        tree.iter()
+            .map(|(depth, label)| format!("{}{label}", "  ".repeat(*depth))),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let output = "0cc v1.8.0
+1curl-sys v0.4.91+curl-8.22.0
+2curl v0.4.51
+3app v0.5.0 (/tmp/app)
+1libz-sys v1.1.29
+2curl-sys v0.4.91+curl-8.22.0 (*)
+1openssl-sys v0.9.117
+2cc-user v0.1.0 (/tmp/app/crates/cc-user)
+";
+
+        assert_eq!(
+            render("cc", &parse_tree(output)),
+            vec![
+                "Direct dependencies pulling in cc:",
+                "  curl v0.4.51, required by app",
+                "  openssl-sys v0.9.117, required by cc-user",
+                "",
+                "cc v1.8.0",
+                "  curl-sys v0.4.91+curl-8.22.0",
+                "    curl v0.4.51",
+                "      app v0.5.0 (/tmp/app)",
+                "  libz-sys v1.1.29",
+                "    curl-sys v0.4.91+curl-8.22.0 (*)",
+                "  openssl-sys v0.9.117",
+                "    cc-user v0.1.0 (/tmp/app/crates/cc-user)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_local_package() {
+        assert!(is_local_package("app v0.5.0 (/tmp/app)"));
+        assert!(is_local_package("app v0.5.0 (C:\\app)"));
+        assert!(!is_local_package("serde_derive v1.0.0 (proc-macro)"));
+        assert!(!is_local_package("curl-sys v0.4.91 (*)"));
+        assert!(!is_local_package(
+            "foo v0.1.0 (https://github.com/foo/foo#abc)"
+        ));
+        assert!(!is_local_package("serde v1.0.0"));
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use crate::{
+    dependency::{Dependencies, DependencyKind},
+    logger,
+};
+
+// Marks the dependencies never referenced in the sources of the package using them, which is only
+// a hint since a crate can be used for its features alone or have a library name of its own
+pub fn annotate(dependencies: &mut Dependencies) {
+    let identifiers = dependencies
+        .cargo_toml_files
+        .keys()
+        .map(|workspace_path| {
+            (
+                workspace_path.clone(),
+                get_package_identifiers(Path::new(workspace_path)),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    for dependency in dependencies.dependencies.iter_mut() {
+        let name = dependency.name.replace('-', "_");
+        dependency.possibly_unused = match dependency.kind {
+            DependencyKind::Workspace => dependency.workspace_users.is_empty(),
+            DependencyKind::Toolchain => false,
+            DependencyKind::Normal | DependencyKind::Dev | DependencyKind::Build => identifiers
+                .get(dependency.workspace_path.as_deref().unwrap_or("."))
+                .is_some_and(|identifiers| !identifiers.contains(&name)),
+        };
+
+        if dependency.possibly_unused {
+            logger::warn(format!(
+                "{} ({}) in {} is possibly unused",
+                dependency.name,
+                dependency.kind.name(),
+                dependency.manifest_path()
+            ));
+        }
+    }
+}
+
+// The identifiers of every Rust file of the package, skipping the build output and nested
+// packages, which have manifests of their own
+fn get_package_identifiers(package_path: &Path) -> HashSet<String> {
+    let mut identifiers = HashSet::new();
+    let mut dirs = vec![package_path.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                if name != "target" && !name.starts_with('.') && !path.join("Cargo.toml").exists() {
+                    dirs.push(path);
+                }
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                if let Ok(source) = std::fs::read_to_string(&path) {
+                    identifiers.extend(get_identifiers(&source));
+                }
+            }
+        }
+    }
+
+    identifiers
+}
+
+fn get_identifiers(source: &str) -> impl Iterator<Item = String> + '_ {
+    source
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|identifier| !identifier.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_identifiers() {
+        let identifiers = get_identifiers("use serde::{Serialize};\nlet _ = toml_edit::value(1);")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            identifiers,
+            vec![
+                "use",
+                "serde",
+                "Serialize",
+                "let",
+                "_",
+                "toml_edit",
+                "value",
+                "1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotate() {
+        let workspace_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/packages/reexports")
+            .to_string_lossy()
+            .to_string();
+        let dependency = |name: &str, kind| crate::dependency::Dependency {
+            name: name.to_string(),
+            kind,
+            workspace_path: Some(workspace_path.clone()),
+            ..Default::default()
+        };
+        let mut dependencies = Dependencies::new(
+            vec![
+                dependency("serde", DependencyKind::Normal),
+                dependency("toml-edit", DependencyKind::Dev),
+                dependency("glob", DependencyKind::Normal),
+                dependency("log", DependencyKind::Workspace),
+            ],
+            HashMap::from([(workspace_path.clone(), Default::default())]),
+        );
+
+        annotate(&mut dependencies);
+        assert!(!dependencies.dependencies[0].possibly_unused);
+        assert!(!dependencies.dependencies[1].possibly_unused);
+        assert!(dependencies.dependencies[2].possibly_unused);
+        assert!(dependencies.dependencies[3].possibly_unused);
+    }
+}
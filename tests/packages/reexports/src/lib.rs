@@ -0,0 +1,3 @@
+mod nested;
+
+pub use serde::Serialize;
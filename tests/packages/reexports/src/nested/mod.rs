@@ -0,0 +1 @@
+pub use toml_edit::DocumentMut;